@@ -0,0 +1,128 @@
+// advanced2
+//
+// This exercise teaches you about receiving and sending native tokens.
+//
+// A message marked `payable` can be called with tokens attached. The
+// contract keeps them, and can send them on with `self.env().transfer`.
+//
+// Your task: Complete a contract that holds deposits and pays them back
+//
+// Execute `inklings verify 05_advanced/advanced2` when you think you're done!
+#![allow(unexpected_cfgs)]
+#[ink::contract]
+mod advanced2 {
+    use ink::primitives::H160;
+    use ink::storage::Mapping;
+    use ink::U256;
+
+    #[derive(Debug, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    pub enum Error {
+        /// Nothing was sent with the deposit
+        ZeroDeposit,
+        /// The caller deposited less than they asked to withdraw
+        InsufficientDeposit,
+        /// Sending the tokens back failed
+        TransferFailed,
+    }
+    pub type Result<T> = core::result::Result<T, Error>;
+
+    #[ink(storage)]
+    #[derive(Default)]
+    pub struct Advanced2 {
+        deposits: Mapping<H160, U256>,
+    }
+
+    impl Advanced2 {
+        #[ink(constructor)]
+        pub fn new() -> Self {
+            Self::default()
+        }
+
+        /// Adds whatever the caller sent to their deposit
+        // TODO: Let this message accept tokens
+        // Hint: #[ink(message, payable)]
+        #[ink(message)]
+        pub fn deposit(&mut self) -> Result<()> {
+            // TODO: Get the amount sent with `self.env().transferred_value()`
+            // If it's zero, return Error::ZeroDeposit
+
+            // TODO: Add the amount to the caller's deposit
+            todo!()
+        }
+
+        /// Sends `amount` of the caller's deposit back to them
+        #[ink(message)]
+        pub fn withdraw(&mut self, amount: U256) -> Result<()> {
+            let caller = self.env().caller();
+            let deposit = self.deposit_of(caller);
+            if deposit < amount {
+                return Err(Error::InsufficientDeposit);
+            }
+
+            self.deposits.insert(caller, &(deposit - amount));
+            // TODO: Send `amount` back to the caller with `self.env().transfer`
+            // It can fail: turn its error into Error::TransferFailed
+            todo!()
+        }
+
+        #[ink(message)]
+        pub fn deposit_of(&self, account: H160) -> U256 {
+            self.deposits.get(account).unwrap_or_default()
+        }
+
+        /// Everything the contract holds
+        #[ink(message)]
+        pub fn total_balance(&self) -> U256 {
+            self.env().balance()
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        fn deposit(contract: &mut Advanced2, from: H160, amount: u128) -> Result<()> {
+            ink::env::test::set_caller(from);
+            ink::env::test::transfer_in(U256::from(amount));
+            contract.deposit()
+        }
+
+        #[ink::test]
+        fn deposit_works() {
+            let accounts = ink::env::test::default_accounts();
+            let mut contract = Advanced2::new();
+
+            assert_eq!(deposit(&mut contract, accounts.alice, 100), Ok(()));
+            assert_eq!(deposit(&mut contract, accounts.alice, 50), Ok(()));
+            assert_eq!(contract.deposit_of(accounts.alice), U256::from(150));
+            assert_eq!(contract.deposit_of(accounts.bob), U256::zero());
+        }
+
+        #[ink::test]
+        fn zero_deposit_fails() {
+            let accounts = ink::env::test::default_accounts();
+            let mut contract = Advanced2::new();
+            assert_eq!(deposit(&mut contract, accounts.alice, 0), Err(Error::ZeroDeposit));
+        }
+
+        #[ink::test]
+        fn withdraw_works() {
+            let accounts = ink::env::test::default_accounts();
+            let mut contract = Advanced2::new();
+            assert_eq!(deposit(&mut contract, accounts.alice, 100), Ok(()));
+
+            assert_eq!(contract.withdraw(U256::from(30)), Ok(()));
+            assert_eq!(contract.deposit_of(accounts.alice), U256::from(70));
+        }
+
+        #[ink::test]
+        fn withdraw_more_than_deposited_fails() {
+            let accounts = ink::env::test::default_accounts();
+            let mut contract = Advanced2::new();
+            assert_eq!(deposit(&mut contract, accounts.alice, 50), Ok(()));
+            assert_eq!(contract.withdraw(U256::from(100)), Err(Error::InsufficientDeposit));
+            assert_eq!(contract.deposit_of(accounts.alice), U256::from(50));
+        }
+    }
+}
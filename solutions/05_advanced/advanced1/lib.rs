@@ -0,0 +1,103 @@
+#![allow(unexpected_cfgs)]
+#[ink::contract]
+mod advanced1 {
+    use ink::codegen::TraitCallBuilder;
+    use ink::primitives::H160;
+
+    /// Anything that can report a value, this contract included
+    #[ink::trait_definition]
+    pub trait Counter {
+        #[ink(message)]
+        fn get_value(&self) -> u32;
+    }
+
+    #[derive(Debug, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    pub enum Error {
+        /// No other contract has been set
+        NoOtherContract,
+        /// The call to the other contract failed
+        CallFailed,
+        /// The sum doesn't fit in a u32
+        Overflow,
+    }
+    pub type Result<T> = core::result::Result<T, Error>;
+
+    #[ink(storage)]
+    pub struct Advanced1 {
+        value: u32,
+        other: Option<H160>,
+    }
+
+    impl Advanced1 {
+        #[ink(constructor)]
+        pub fn new(value: u32) -> Self {
+            Self { value, other: None }
+        }
+
+        /// Sets the contract whose value `other_value` reads
+        #[ink(message)]
+        pub fn set_other(&mut self, other: H160) {
+            self.other = Some(other);
+        }
+
+        #[ink(message)]
+        pub fn other(&self) -> Option<H160> {
+            self.other
+        }
+
+        /// Asks the other contract for its value
+        #[ink(message)]
+        pub fn other_value(&self) -> Result<u32> {
+            let address = self.other.ok_or(Error::NoOtherContract)?;
+            let other: ink::contract_ref_from_path!(Counter) = address.into();
+            match other.call().get_value().try_invoke() {
+                Ok(Ok(value)) => Ok(value),
+                _ => Err(Error::CallFailed),
+            }
+        }
+
+        /// This contract's value plus the other contract's
+        #[ink(message)]
+        pub fn total(&self) -> Result<u32> {
+            self.value.checked_add(self.other_value()?).ok_or(Error::Overflow)
+        }
+    }
+
+    impl Counter for Advanced1 {
+        #[ink(message)]
+        fn get_value(&self) -> u32 {
+            self.value
+        }
+    }
+
+    // The off-chain test environment can't call other contracts, so these
+    // tests cover everything up to the call itself
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[ink::test]
+        fn get_value_works() {
+            let contract = Advanced1::new(42);
+            assert_eq!(Counter::get_value(&contract), 42);
+        }
+
+        #[ink::test]
+        fn set_other_works() {
+            let accounts = ink::env::test::default_accounts();
+            let mut contract = Advanced1::new(1);
+            assert_eq!(contract.other(), None);
+
+            contract.set_other(accounts.bob);
+            assert_eq!(contract.other(), Some(accounts.bob));
+        }
+
+        #[ink::test]
+        fn calls_without_other_contract_fail() {
+            let contract = Advanced1::new(1);
+            assert_eq!(contract.other_value(), Err(Error::NoOtherContract));
+            assert_eq!(contract.total(), Err(Error::NoOtherContract));
+        }
+    }
+}
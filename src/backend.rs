@@ -0,0 +1,560 @@
+//! What actually compiles, tests and deploys exercises. Verification and
+//! `inklings run` go through a `Backend` instead of running cargo
+//! themselves, so a custom toolchain can be plugged in and the verification
+//! flow can be tested against `FakeBackend` without compiling anything.
+//!
+//! `[metadata] backend` in the manifest picks the built-in one: `cargo`
+//! (the default) or `cargo-contract`, which adds a `cargo contract build`
+//! stage to every mode so an exercise only passes once it produces PolkaVM
+//! artifacts.
+
+use crate::Curriculum;
+use crate::cargo::{self, CancelToken, RunError, Stream};
+use crate::curriculum::{Mode, Step};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::{self, Command};
+use std::sync::Mutex;
+
+/// What a backend operation printed and whether it succeeded.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Output {
+    pub success: bool,
+    /// For compile and test stages, cargo's `--message-format=json` messages
+    /// and libtest's results.
+    pub stdout: String,
+    pub stderr: String,
+}
+
+impl Output {
+    pub fn passed(stdout: impl Into<String>) -> Output {
+        Output { success: true, stdout: stdout.into(), stderr: String::new() }
+    }
+
+    pub fn failed(stdout: impl Into<String>) -> Output {
+        Output { success: false, stdout: stdout.into(), stderr: String::new() }
+    }
+}
+
+impl From<process::Output> for Output {
+    fn from(output: process::Output) -> Self {
+        Output {
+            success: output.status.success(),
+            stdout: String::from_utf8_lossy(&output.stdout).into_owned(),
+            stderr: String::from_utf8_lossy(&output.stderr).into_owned(),
+        }
+    }
+}
+
+/// What `Backend::deploy` instantiates, and on which node.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Deployment {
+    pub constructor: String,
+    /// In the syntax `cargo contract` takes, e.g. `true` or `"text"`.
+    pub args: Vec<String>,
+    /// Transferred to the new contract; payable constructors only.
+    pub value: u128,
+    /// The account paying for it.
+    pub suri: String,
+    /// The node's websocket endpoint.
+    pub url: String,
+}
+
+impl Deployment {
+    /// Calls `constructor` without arguments as `//Alice` on a local dev node.
+    pub fn new(constructor: impl Into<String>) -> Self {
+        Deployment {
+            constructor: constructor.into(),
+            args: Vec::new(),
+            value: 0,
+            suri: "//Alice".to_string(),
+            url: "ws://127.0.0.1:9944".to_string(),
+        }
+    }
+
+    pub fn args(mut self, args: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.args = args.into_iter().map(Into::into).collect();
+        self
+    }
+}
+
+/// Builds and runs exercises. Each operation hands cargo's output to
+/// `on_line` as it arrives and stops with `RunError::Cancelled` once `cancel`
+/// fires.
+pub trait Backend: Send + Sync {
+    /// The stages verifying an exercise of `mode` runs, in order.
+    fn steps(&self, mode: Mode) -> Vec<Step> {
+        mode.steps()
+    }
+
+    /// Runs a stage that compiles the crate in `exercise`: `check`, `build` or `clippy`.
+    fn compile(
+        &self,
+        exercise: &Path,
+        step: &Step,
+        cancel: &CancelToken,
+        on_line: &mut dyn FnMut(Stream, &str),
+    ) -> Result<Output, RunError>;
+
+    /// Runs a stage that tests the crate in `exercise`.
+    fn test(
+        &self,
+        exercise: &Path,
+        step: &Step,
+        cancel: &CancelToken,
+        on_line: &mut dyn FnMut(Stream, &str),
+    ) -> Result<Output, RunError>;
+
+    /// Builds the contract in `exercise` into deployable artifacts: the
+    /// PolkaVM blob and its metadata. `step.args` follow the manifest path.
+    fn contract_build(
+        &self,
+        exercise: &Path,
+        step: &Step,
+        cancel: &CancelToken,
+        on_line: &mut dyn FnMut(Stream, &str),
+    ) -> Result<Output, RunError>;
+
+    /// Instantiates the contract `contract_build` built from `exercise` on
+    /// a node. stdout carries what the node reported, including the new
+    /// contract's address.
+    fn deploy(
+        &self,
+        exercise: &Path,
+        deployment: &Deployment,
+        cancel: &CancelToken,
+        on_line: &mut dyn FnMut(Stream, &str),
+    ) -> Result<Output, RunError>;
+
+    /// Compiles the `inklings run` driver crate in `driver` into a test
+    /// binary without running it. stdout carries cargo's JSON messages,
+    /// which name the binary.
+    fn build_driver(
+        &self,
+        driver: &Path,
+        cancel: &CancelToken,
+        on_line: &mut dyn FnMut(Stream, &str),
+    ) -> Result<Output, RunError>;
+}
+
+/// The stage `cargo-contract` adds after every mode's own.
+pub const CONTRACT_BUILD_STEP: Step = Step {
+    subcommand: "contract build",
+    running: "Building artifacts for",
+    args: &[],
+    runs_tests: false,
+    passed: "✓ Contract artifacts built!",
+    failed: "✗ cargo contract build failed!",
+};
+
+/// Why a `cargo contract` operation couldn't start.
+const CARGO_CONTRACT_MISSING: &str = "cargo-contract isn't installed (cargo install cargo-contract)";
+
+/// Passes a `cargo contract` result on, except that a missing
+/// cargo-contract is reported as the missing toolchain it is rather than as
+/// a build that failed.
+fn cargo_contract(result: Result<Output, RunError>) -> Result<Output, RunError> {
+    let missing = || Err(RunError::Io(io::Error::new(io::ErrorKind::NotFound, CARGO_CONTRACT_MISSING)));
+    match result {
+        Err(RunError::Io(e)) if e.kind() == io::ErrorKind::NotFound => missing(),
+        // cargo itself ran, but has no `contract` subcommand to hand over to
+        Ok(output) if !output.success && output.stderr.contains("no such command: `contract`") => missing(),
+        result => result,
+    }
+}
+
+/// Which built-in backend the manifest asks for.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum BackendKind {
+    #[default]
+    Cargo,
+    CargoContract,
+}
+
+/// The backend `[metadata] backend` selects, building into the curriculum's target directory.
+pub fn configured(config: &Curriculum) -> Box<dyn Backend> {
+    configured_in(config, config.target_dir())
+}
+
+/// Like `configured`, but building into `target_dir`.
+pub fn configured_in(config: &Curriculum, target_dir: PathBuf) -> Box<dyn Backend> {
+    let cargo = CargoBackend::new(target_dir);
+    match config.metadata.as_ref().map(|m| m.backend).unwrap_or_default() {
+        BackendKind::Cargo => Box::new(cargo),
+        BackendKind::CargoContract => Box::new(CargoContractBackend(cargo)),
+    }
+}
+
+/// Runs `cargo <subcommand>` for each stage, building into one target directory.
+#[derive(Debug, Clone)]
+pub struct CargoBackend {
+    target_dir: PathBuf,
+}
+
+impl CargoBackend {
+    pub fn new(target_dir: PathBuf) -> Self {
+        CargoBackend { target_dir }
+    }
+
+    /// `cargo contract build` for the contract in `exercise`.
+    fn contract_build_command(&self, exercise: &Path, step: &Step) -> Command {
+        let mut command = cargo::command(&self.target_dir);
+        command.args(["contract", "build", "--manifest-path"]).arg(exercise.join("Cargo.toml")).args(step.args);
+        command
+    }
+
+    /// `cargo contract instantiate` for the contract in `exercise`.
+    fn deploy_command(&self, exercise: &Path, deployment: &Deployment) -> Command {
+        let mut command = cargo::command(&self.target_dir);
+        command
+            .args(["contract", "instantiate", "--manifest-path"])
+            .arg(exercise.join("Cargo.toml"))
+            .args(["--constructor", &deployment.constructor])
+            .args(["--suri", &deployment.suri])
+            .args(["--url", &deployment.url])
+            .args(["--skip-confirm", "--execute"]);
+        if deployment.value > 0 {
+            command.arg("--value").arg(deployment.value.to_string());
+        }
+        // Last, since it takes every value up to the next flag
+        if !deployment.args.is_empty() {
+            command.arg("--args").args(&deployment.args);
+        }
+        command
+    }
+
+    fn stage(
+        &self,
+        exercise: &Path,
+        step: &Step,
+        cancel: &CancelToken,
+        on_line: &mut dyn FnMut(Stream, &str),
+    ) -> Result<Output, RunError> {
+        cargo::run(
+            cargo::command(&self.target_dir)
+                .arg(step.subcommand)
+                .arg("--manifest-path")
+                .arg(exercise.join("Cargo.toml"))
+                .arg("--message-format=json")
+                .args(step.args)
+                .env("RUST_BACKTRACE", "0"),
+            cancel,
+            on_line,
+        )
+        .map(Output::from)
+    }
+}
+
+impl Backend for CargoBackend {
+    fn compile(
+        &self,
+        exercise: &Path,
+        step: &Step,
+        cancel: &CancelToken,
+        on_line: &mut dyn FnMut(Stream, &str),
+    ) -> Result<Output, RunError> {
+        self.stage(exercise, step, cancel, on_line)
+    }
+
+    fn test(
+        &self,
+        exercise: &Path,
+        step: &Step,
+        cancel: &CancelToken,
+        on_line: &mut dyn FnMut(Stream, &str),
+    ) -> Result<Output, RunError> {
+        self.stage(exercise, step, cancel, on_line)
+    }
+
+    fn contract_build(
+        &self,
+        exercise: &Path,
+        step: &Step,
+        cancel: &CancelToken,
+        on_line: &mut dyn FnMut(Stream, &str),
+    ) -> Result<Output, RunError> {
+        cargo_contract(cargo::run(&mut self.contract_build_command(exercise, step), cancel, on_line).map(Output::from))
+    }
+
+    fn deploy(
+        &self,
+        exercise: &Path,
+        deployment: &Deployment,
+        cancel: &CancelToken,
+        on_line: &mut dyn FnMut(Stream, &str),
+    ) -> Result<Output, RunError> {
+        cargo_contract(cargo::run(&mut self.deploy_command(exercise, deployment), cancel, on_line).map(Output::from))
+    }
+
+    fn build_driver(
+        &self,
+        driver: &Path,
+        cancel: &CancelToken,
+        on_line: &mut dyn FnMut(Stream, &str),
+    ) -> Result<Output, RunError> {
+        cargo::run(
+            cargo::command(&self.target_dir)
+                .args(["test", "--no-run", "--lib", "--message-format=json", "--manifest-path"])
+                .arg(driver.join("Cargo.toml")),
+            cancel,
+            on_line,
+        )
+        .map(Output::from)
+    }
+}
+
+/// Like `CargoBackend`, but every mode ends with `CONTRACT_BUILD_STEP`, so
+/// an exercise only passes once it also produces PolkaVM artifacts.
+#[derive(Debug, Clone)]
+pub struct CargoContractBackend(pub CargoBackend);
+
+impl Backend for CargoContractBackend {
+    fn steps(&self, mode: Mode) -> Vec<Step> {
+        let mut steps = mode.steps();
+        steps.push(CONTRACT_BUILD_STEP);
+        steps
+    }
+
+    fn compile(
+        &self,
+        exercise: &Path,
+        step: &Step,
+        cancel: &CancelToken,
+        on_line: &mut dyn FnMut(Stream, &str),
+    ) -> Result<Output, RunError> {
+        if step.subcommand == CONTRACT_BUILD_STEP.subcommand {
+            return self.contract_build(exercise, step, cancel, on_line);
+        }
+        self.0.compile(exercise, step, cancel, on_line)
+    }
+
+    fn test(
+        &self,
+        exercise: &Path,
+        step: &Step,
+        cancel: &CancelToken,
+        on_line: &mut dyn FnMut(Stream, &str),
+    ) -> Result<Output, RunError> {
+        self.0.test(exercise, step, cancel, on_line)
+    }
+
+    fn contract_build(
+        &self,
+        exercise: &Path,
+        step: &Step,
+        cancel: &CancelToken,
+        on_line: &mut dyn FnMut(Stream, &str),
+    ) -> Result<Output, RunError> {
+        self.0.contract_build(exercise, step, cancel, on_line)
+    }
+
+    fn deploy(
+        &self,
+        exercise: &Path,
+        deployment: &Deployment,
+        cancel: &CancelToken,
+        on_line: &mut dyn FnMut(Stream, &str),
+    ) -> Result<Output, RunError> {
+        self.0.deploy(exercise, deployment, cancel, on_line)
+    }
+
+    fn build_driver(
+        &self,
+        driver: &Path,
+        cancel: &CancelToken,
+        on_line: &mut dyn FnMut(Stream, &str),
+    ) -> Result<Output, RunError> {
+        self.0.build_driver(driver, cancel, on_line)
+    }
+}
+
+/// Compiles nothing: every stage returns the output scripted for its
+/// subcommand (`contract build`, `deploy` and `driver` for the others), or passes
+/// silently if none was. Records what ran, for tests that check the flow.
+#[derive(Debug, Default)]
+pub struct FakeBackend {
+    outputs: HashMap<String, Output>,
+    calls: Mutex<Vec<String>>,
+}
+
+impl FakeBackend {
+    pub fn new() -> Self {
+        FakeBackend::default()
+    }
+
+    /// Scripts what the stage running `subcommand` returns.
+    pub fn with(mut self, subcommand: &str, output: Output) -> Self {
+        self.outputs.insert(subcommand.to_string(), output);
+        self
+    }
+
+    /// The subcommands run so far, in order.
+    pub fn calls(&self) -> Vec<String> {
+        self.calls.lock().unwrap().clone()
+    }
+
+    fn respond(&self, subcommand: &str, on_line: &mut dyn FnMut(Stream, &str)) -> Output {
+        self.calls.lock().unwrap().push(subcommand.to_string());
+        let output = self.outputs.get(subcommand).cloned().unwrap_or_else(|| Output::passed(""));
+        for line in output.stdout.lines() {
+            on_line(Stream::Stdout, line);
+        }
+        for line in output.stderr.lines() {
+            on_line(Stream::Stderr, line);
+        }
+        output
+    }
+
+    fn stage(
+        &self,
+        subcommand: &str,
+        cancel: &CancelToken,
+        on_line: &mut dyn FnMut(Stream, &str),
+    ) -> Result<Output, RunError> {
+        if cancel.is_cancelled() {
+            return Err(RunError::Cancelled);
+        }
+        Ok(self.respond(subcommand, on_line))
+    }
+}
+
+impl Backend for FakeBackend {
+    fn compile(
+        &self,
+        _exercise: &Path,
+        step: &Step,
+        cancel: &CancelToken,
+        on_line: &mut dyn FnMut(Stream, &str),
+    ) -> Result<Output, RunError> {
+        self.stage(step.subcommand, cancel, on_line)
+    }
+
+    fn test(
+        &self,
+        _exercise: &Path,
+        step: &Step,
+        cancel: &CancelToken,
+        on_line: &mut dyn FnMut(Stream, &str),
+    ) -> Result<Output, RunError> {
+        self.stage(step.subcommand, cancel, on_line)
+    }
+
+    fn contract_build(
+        &self,
+        _exercise: &Path,
+        _step: &Step,
+        cancel: &CancelToken,
+        on_line: &mut dyn FnMut(Stream, &str),
+    ) -> Result<Output, RunError> {
+        self.stage("contract build", cancel, on_line)
+    }
+
+    fn deploy(
+        &self,
+        _exercise: &Path,
+        _deployment: &Deployment,
+        cancel: &CancelToken,
+        on_line: &mut dyn FnMut(Stream, &str),
+    ) -> Result<Output, RunError> {
+        self.stage("deploy", cancel, on_line)
+    }
+
+    fn build_driver(
+        &self,
+        _driver: &Path,
+        cancel: &CancelToken,
+        on_line: &mut dyn FnMut(Stream, &str),
+    ) -> Result<Output, RunError> {
+        self.stage("driver", cancel, on_line)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stages(backend: &dyn Backend, mode: Mode) -> Vec<&'static str> {
+        backend.steps(mode).iter().map(|s| s.subcommand).collect()
+    }
+
+    #[test]
+    fn manifest_selects_backend() {
+        let mut config = Curriculum::load().unwrap();
+        assert_eq!(stages(&*configured(&config), Mode::Test), ["check", "test"]);
+
+        config.metadata.as_mut().unwrap().backend = BackendKind::CargoContract;
+        assert_eq!(stages(&*configured(&config), Mode::Test), ["check", "test", "contract build"]);
+    }
+
+    #[test]
+    fn contract_build_follows_every_mode() {
+        let cargo = CargoBackend::new(PathBuf::from("target"));
+        let contract = CargoContractBackend(cargo.clone());
+        for mode in [Mode::Compile, Mode::Test, Mode::Clippy, Mode::NoStd, Mode::E2e] {
+            assert_eq!(cargo.steps(mode), mode.steps());
+            let mut expected = mode.steps();
+            expected.push(CONTRACT_BUILD_STEP);
+            assert_eq!(contract.steps(mode), expected, "{:?}", mode);
+        }
+    }
+
+    #[test]
+    fn contract_build_forwards_step_args() {
+        let cargo = CargoBackend::new(PathBuf::from("target"));
+        let step = Step { args: &["--release"], ..CONTRACT_BUILD_STEP };
+        let command = cargo.contract_build_command(Path::new("exercises/01_intro/intro1"), &step);
+        let args: Vec<_> = command.get_args().map(|a| a.to_string_lossy().into_owned()).collect();
+        assert_eq!(
+            args,
+            ["contract", "build", "--manifest-path", "exercises/01_intro/intro1/Cargo.toml", "--release"]
+        );
+    }
+
+    #[test]
+    fn deploy_instantiates_on_the_node() {
+        let cargo = CargoBackend::new(PathBuf::from("target"));
+        let deployment = Deployment { value: 5, ..Deployment::new("new").args(["1000", "true"]) };
+        let command = cargo.deploy_command(Path::new("exercises/01_intro/intro1"), &deployment);
+        let args: Vec<_> = command.get_args().map(|a| a.to_string_lossy().into_owned()).collect();
+        assert_eq!(
+            args,
+            [
+                "contract",
+                "instantiate",
+                "--manifest-path",
+                "exercises/01_intro/intro1/Cargo.toml",
+                "--constructor",
+                "new",
+                "--suri",
+                "//Alice",
+                "--url",
+                "ws://127.0.0.1:9944",
+                "--skip-confirm",
+                "--execute",
+                "--value",
+                "5",
+                "--args",
+                "1000",
+                "true"
+            ]
+        );
+    }
+
+    #[test]
+    fn missing_cargo_contract_is_a_missing_toolchain() {
+        let missing = |result| matches!(cargo_contract(result), Err(RunError::Io(e)) if e.kind() == io::ErrorKind::NotFound);
+        let no_subcommand = Output {
+            success: false,
+            stdout: String::new(),
+            stderr: "error: no such command: `contract`\n".to_string(),
+        };
+        assert!(missing(Ok(no_subcommand)));
+        assert!(missing(Err(RunError::Io(io::ErrorKind::NotFound.into()))));
+
+        let build_failed = Output { success: false, stdout: String::new(), stderr: "error[E0425]".to_string() };
+        assert_eq!(cargo_contract(Ok(build_failed.clone())).unwrap(), build_failed);
+    }
+}
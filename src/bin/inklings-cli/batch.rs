@@ -0,0 +1,336 @@
+//! `inklings verify --all/--category/--path`: verifies many exercises at once
+//! and reports them in one table.
+
+use crate::error::CliError;
+use crate::{load_progress, progress, report, update_progress};
+use colored::*;
+use indicatif::{ProgressBar, ProgressStyle};
+use inklings::{Backend, CancelToken, Curriculum, Exercise, Progress, TestScore, Verification, Verifier, VerifyOutcome};
+use inklings::backend;
+use std::fmt;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::sync::mpsc::channel;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Which exercises to verify.
+pub enum Selection {
+    All,
+    Category(String),
+    Path(String),
+}
+
+impl fmt::Display for Selection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Selection::All => f.write_str("all exercises"),
+            Selection::Category(name) => write!(f, "category '{}'", name),
+            Selection::Path(name) => write!(f, "learning path '{}'", name),
+        }
+    }
+}
+
+/// How one exercise came out.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Status {
+    Passed,
+    /// Passed before and hasn't changed since.
+    Unchanged,
+    /// Still has `todo!()`s or an `I AM NOT DONE` marker.
+    NotStarted,
+    /// The cargo subcommand of the stage that failed.
+    Failed(&'static str),
+    /// cargo couldn't be run at all.
+    Error(String),
+}
+
+impl Status {
+    fn passed(&self) -> bool {
+        matches!(self, Status::Passed | Status::Unchanged)
+    }
+
+    fn label(&self) -> String {
+        match self {
+            Status::Passed => "✓ passed".to_string(),
+            Status::Unchanged => "✓ unchanged".to_string(),
+            Status::NotStarted => "· not started".to_string(),
+            Status::Failed(stage) => format!("✗ cargo {} failed", stage),
+            Status::Error(e) => format!("✗ couldn't run cargo: {}", e),
+        }
+    }
+
+    /// The name `--format json` reports the status under.
+    fn kind(&self) -> &'static str {
+        match self {
+            Status::Passed => "passed",
+            Status::Unchanged => "unchanged",
+            Status::NotStarted => "not_started",
+            Status::Failed(_) => "failed",
+            Status::Error(_) => "error",
+        }
+    }
+
+    fn paint(&self, text: String) -> ColoredString {
+        match self {
+            Status::Passed | Status::Unchanged => text.green(),
+            Status::NotStarted => text.yellow(),
+            Status::Failed(_) | Status::Error(_) => text.red(),
+        }
+    }
+}
+
+struct Row {
+    id: String,
+    status: Status,
+    score: Option<TestScore>,
+    /// What to record in progress; None if cargo couldn't be run.
+    verification: Option<Verification>,
+    elapsed: Duration,
+}
+
+/// Resolves the selection to exercises in curriculum order.
+fn select<'a>(config: &'a Curriculum, selection: &Selection) -> Result<Vec<&'a Exercise>, String> {
+    let by_ids = |ids: &[String]| ids.iter().filter_map(|id| config.exercises.iter().find(|e| &e.id == id)).collect();
+    match selection {
+        Selection::All => Ok(config.exercises.iter().collect()),
+        Selection::Category(name) => config
+            .categories
+            .get(name)
+            .map(|category| by_ids(&category.exercises))
+            .ok_or_else(|| unknown("category", name, config.categories.keys())),
+        Selection::Path(name) => config
+            .learning_paths
+            .get(name)
+            .map(|ids| by_ids(ids))
+            .ok_or_else(|| unknown("learning path", name, config.learning_paths.keys())),
+    }
+}
+
+fn unknown<'a>(kind: &str, name: &str, known: impl Iterator<Item = &'a String>) -> String {
+    let known: Vec<_> = known.map(String::as_str).collect();
+    format!("Unknown {} '{}'. Available: {}", kind, name, known.join(", "))
+}
+
+/// Where job `job` builds. cargo locks a target directory for the length of
+/// a build, so jobs sharing one would take turns; every job past the first
+/// gets its own, inside the shared one, and compiles the ink! dependencies
+/// there once. That costs a full dependency build and its disk space per
+/// extra job, which is why `--jobs` defaults to 1.
+fn job_target_dir(config: &Curriculum, job: usize) -> PathBuf {
+    let shared = config.target_dir();
+    match job {
+        0 => shared,
+        _ => shared.join("inklings").join("jobs").join(job.to_string()),
+    }
+}
+
+/// Verifies one exercise without printing anything.
+fn verify_quietly(
+    config: &Curriculum,
+    backend: &dyn Backend,
+    info: &Exercise,
+    progress: &Progress,
+    fresh: bool,
+) -> Row {
+    let started = Instant::now();
+    // Prerequisites only guide a learner through exercises one at a time
+    let verifier = Verifier::new(config).backend(backend).force(true).fresh(fresh);
+    let result = verifier.verify(info, progress, &CancelToken::default(), |_| {});
+    let status = match &result {
+        Ok(verification) => match &verification.outcome {
+            VerifyOutcome::Passed => Status::Passed,
+            VerifyOutcome::Unchanged => Status::Unchanged,
+            VerifyOutcome::Pending(_) => Status::NotStarted,
+            VerifyOutcome::Failed(failure) => Status::Failed(failure.stage.step.subcommand),
+            VerifyOutcome::Locked { .. } | VerifyOutcome::Cancelled => {
+                unreachable!("batch runs are forced and never cancelled")
+            }
+        },
+        Err(e) => Status::Error(e.to_string()),
+    };
+    let verification = result.ok();
+    Row {
+        id: info.id.clone(),
+        status,
+        score: verification.as_ref().and_then(|v| v.score),
+        verification,
+        elapsed: started.elapsed(),
+    }
+}
+
+fn print_table(rows: &[Row]) {
+    let width = rows.iter().map(|r| r.id.len()).max().unwrap_or(0).max("Exercise".len());
+    println!("{}", format!("{:<width$}  {:<22}  {:>7}  {:>7}", "Exercise", "Result", "Tests", "Time").bold());
+    for row in rows {
+        let tests = row.score.map(|s| format!("{}/{}", s.passed, s.total)).unwrap_or_else(|| "-".to_string());
+        // Pad before colouring, or the escape codes throw the columns off
+        let status = row.status.paint(format!("{:<22}", row.status.label()));
+        println!(
+            "{:<width$}  {}  {:>7}  {:>7}",
+            row.id,
+            status,
+            tests,
+            progress::format_elapsed(row.elapsed)
+        );
+    }
+}
+
+/// The error for the run as a whole: the most fundamental failure among
+/// the rows, naming the first exercise it happened to.
+fn overall_error(rows: &[Row]) -> Option<CliError> {
+    let first = |matches: fn(&Row) -> bool| rows.iter().find(|row| matches(row));
+    for row in rows {
+        if let Status::Error(e) = &row.status {
+            return Some(CliError::ToolchainMissing(e.clone()));
+        }
+    }
+    if let Some(row) = first(|r| matches!(r.status, Status::Failed(_)) && r.score.is_none()) {
+        return Some(CliError::CompileFailed(row.id.clone()));
+    }
+    if let Some(row) = first(|r| matches!(r.status, Status::Failed(_))) {
+        return Some(CliError::TestsFailed(row.id.clone()));
+    }
+    first(|r| r.status == Status::NotStarted).map(|row| CliError::Unfinished(row.id.clone()))
+}
+
+/// Verifies the selected exercises on `jobs` threads, each building into its
+/// own target directory, and records progress for each. Fails if any of them
+/// didn't pass.
+pub fn run(config: &Curriculum, selection: Selection, jobs: usize, fresh: bool) -> Result<(), CliError> {
+    let json = report::is_json();
+    let exercises = match select(config, &selection) {
+        Ok(exercises) => exercises,
+        Err(e) => {
+            if !json {
+                println!("{}", e.red());
+            }
+            return Err(CliError::NotFound(selection.to_string()));
+        }
+    };
+    let jobs = jobs.clamp(1, exercises.len().max(1));
+    let snapshot = load_progress()?;
+    let bar = if json {
+        ProgressBar::hidden()
+    } else {
+        println!("{}", format!("🔍 Verifying {} exercises on {} jobs...", exercises.len(), jobs).cyan());
+        println!();
+        ProgressBar::new(exercises.len() as u64)
+    };
+    bar.set_style(ProgressStyle::with_template("{bar:30.cyan/blue} {pos}/{len} {elapsed:.dim}").unwrap());
+
+    let queue = Mutex::new(exercises.iter().enumerate());
+    let (tx, rx) = channel();
+    let mut rows: Vec<Option<Row>> = exercises.iter().map(|_| None).collect();
+    let mut saved = Ok(());
+
+    thread::scope(|scope| {
+        for job in 0..jobs {
+            let tx = tx.clone();
+            let (queue, snapshot) = (&queue, &snapshot);
+            scope.spawn(move || {
+                let backend = backend::configured_in(config, job_target_dir(config, job));
+                loop {
+                    // Hold the lock only long enough to pick the next exercise
+                    let next = queue.lock().unwrap().next();
+                    let Some((index, info)) = next else {
+                        return;
+                    };
+                    let _ = tx.send((index, verify_quietly(config, &*backend, info, snapshot, fresh)));
+                }
+            });
+        }
+        drop(tx);
+
+        // Progress is recorded here, on one thread, as each result comes in
+        for (index, mut row) in rx {
+            if let Some(verification) = row.verification.take()
+                && saved.is_ok()
+            {
+                // Sweeping over a set of exercises isn't an attempt at any of them
+                let verification = Verification { attempted: false, ..verification };
+                saved = update_progress(|progress| progress.record(&row.id, &verification));
+            }
+            if json {
+                report::emit(
+                    "exercise_verified",
+                    serde_json::json!({
+                        "exercise": row.id,
+                        "status": row.status.kind(),
+                        "tests": report::score(row.score),
+                        "elapsed_ms": row.elapsed.as_millis() as u64,
+                    }),
+                );
+            } else {
+                bar.println(format!("{} {}", row.status.paint(row.status.label()), row.id));
+            }
+            bar.inc(1);
+            rows[index] = Some(row);
+        }
+    });
+    bar.finish_and_clear();
+    saved?;
+
+    let rows: Vec<Row> = rows.into_iter().flatten().collect();
+    let passed = rows.iter().filter(|r| r.status.passed()).count();
+    if json {
+        report::emit("summary", serde_json::json!({ "passed": passed, "total": rows.len() }));
+        return overall_error(&rows).map_or(Ok(()), Err);
+    }
+
+    println!();
+    print_table(&rows);
+
+    println!();
+    let summary = format!("{}/{} exercises passed", passed, rows.len());
+    match overall_error(&rows) {
+        None => {
+            println!("{}", summary.bright_green().bold());
+            Ok(())
+        }
+        Some(error) => {
+            println!("{}", summary.red().bold());
+            Err(error)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn selects_categories_and_paths() {
+        let config = Curriculum::load().unwrap();
+        assert_eq!(select(&config, &Selection::All).unwrap().len(), config.exercises.len());
+
+        let intro: Vec<_> = select(&config, &Selection::Category("intro".into())).unwrap().iter().map(|e| e.id.as_str()).collect();
+        assert_eq!(intro, ["01_intro/intro1", "01_intro/intro2"]);
+
+        let err = select(&config, &Selection::Path("nope".into())).err().unwrap();
+        assert!(err.starts_with("Unknown learning path 'nope'"));
+    }
+
+    #[test]
+    fn jobs_build_in_separate_directories() {
+        let config = Curriculum::load().unwrap();
+        assert_eq!(job_target_dir(&config, 0), config.target_dir());
+        let dirs: HashSet<_> = (0..4).map(|job| job_target_dir(&config, job)).collect();
+        assert_eq!(dirs.len(), 4);
+    }
+
+    #[test]
+    fn cargo_errors_are_reported_once() {
+        let row = Row {
+            id: "01_intro/intro1".to_string(),
+            status: Status::Error("No such file or directory".to_string()),
+            score: None,
+            verification: None,
+            elapsed: Duration::ZERO,
+        };
+        assert_eq!(row.status.label(), "✗ couldn't run cargo: No such file or directory");
+        let error = overall_error(&[row]).unwrap();
+        assert_eq!(error.to_string(), "couldn't run cargo: No such file or directory");
+    }
+}
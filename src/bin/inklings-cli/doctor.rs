@@ -0,0 +1,80 @@
+use crate::error::CliError;
+use crate::report;
+use colored::*;
+use inklings::Curriculum;
+use inklings::doctor::{Issue, check};
+use std::path::Path;
+
+/// `inklings doctor`: prints every issue and fails with a config error if
+/// any were found.
+pub fn run() -> Result<(), CliError> {
+    if report::is_json() {
+        let config = Curriculum::load().map_err(|e| CliError::Config(e.to_string()))?;
+        let issues: Vec<_> = check(&config, Path::new(".")).iter().map(Issue::to_string).collect();
+        report::emit("doctor", serde_json::json!({ "issues": issues }));
+        if !issues.is_empty() {
+            return Err(CliError::Config(format!("Found {} issue(s) in info/exercises.toml.", issues.len())));
+        }
+        return Ok(());
+    }
+
+    println!("{}", "🩺 Checking info/exercises.toml...".cyan());
+    println!();
+
+    let config = match Curriculum::load() {
+        Ok(config) => config,
+        Err(e) => {
+            println!("{}", format!("✗ Could not load info/exercises.toml: {}", e).red());
+            return Err(CliError::Config(e.to_string()));
+        }
+    };
+
+    if let Some(metadata) = &config.metadata {
+        println!("{}", format!("Manifest version {}", metadata.version).dimmed());
+    }
+
+    let issues = check(&config, Path::new("."));
+    if issues.is_empty() {
+        println!("{}", format!("✓ {} exercises, no issues found.", config.exercises.len()).green());
+        return Ok(());
+    }
+
+    for issue in &issues {
+        println!("  {} {}", "✗".red(), issue);
+    }
+    println!();
+    let summary = format!("Found {} issue(s) in info/exercises.toml.", issues.len());
+    println!("{}", summary.red().bold());
+    Err(CliError::Config(summary))
+}
+
+/// Startup check run before every other subcommand, so a broken manifest
+/// fails fast. Other issues are printed as warnings and don't stop anything;
+/// with `--format json` they go to stderr to keep stdout parseable.
+pub fn ensure_valid() -> Result<(), CliError> {
+    let issues = match Curriculum::load() {
+        Ok(config) => check(&config, Path::new(".")),
+        Err(_) => return Err(broken()),
+    };
+    let (breaking, warnings): (Vec<_>, Vec<_>) = issues.into_iter().partition(Issue::is_breaking);
+    if !breaking.is_empty() {
+        return Err(broken());
+    }
+
+    for issue in &warnings {
+        if report::is_json() {
+            eprintln!("warning: {}", issue);
+        } else {
+            println!("{} {}", "⚠".yellow(), issue.to_string().yellow());
+        }
+    }
+    Ok(())
+}
+
+fn broken() -> CliError {
+    if !report::is_json() {
+        println!("{}", "✗ info/exercises.toml does not match the exercise tree.".red().bold());
+        println!("{}", "Run 'inklings doctor' for details.".yellow());
+    }
+    CliError::Config("info/exercises.toml does not match the exercise tree".to_string())
+}
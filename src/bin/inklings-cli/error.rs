@@ -0,0 +1,118 @@
+//! How a subcommand can fail. Each kind has its own exit code so scripts,
+//! editors and CI graders can branch on the outcome without scraping output.
+//!
+//! Errors are printed where they happen, alongside whatever guidance fits
+//! there (suggestions, diagnostics, hints); `main` only turns them into the
+//! process exit code, and with `--format json` into a final `error` object.
+
+use std::fmt;
+
+/// Exit codes, kept stable for anything scripting against `inklings`.
+/// 2 covers every usage error, whether clap rejects the command line or a
+/// `CliError::Usage` catches what clap can't.
+pub mod exit_code {
+    pub const UNFINISHED: i32 = 1;
+    pub const USAGE: i32 = 2;
+    pub const NOT_FOUND: i32 = 3;
+    pub const COMPILE_FAILED: i32 = 4;
+    pub const TESTS_FAILED: i32 = 5;
+    pub const CONFIG: i32 = 6;
+    pub const TOOLCHAIN_MISSING: i32 = 7;
+    pub const IO: i32 = 8;
+    pub const INTERRUPTED: i32 = 130;
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CliError {
+    /// No exercise, category or learning path goes by that name.
+    NotFound(String),
+    /// A check, build or clippy stage rejected the exercise.
+    CompileFailed(String),
+    /// The exercise compiles but some of its tests fail.
+    TestsFailed(String),
+    /// Verification didn't get to cargo: `todo!()`s remain, or enforced
+    /// prerequisites are incomplete.
+    Unfinished(String),
+    /// info/exercises.toml is missing, unreadable or inconsistent.
+    Config(String),
+    /// cargo couldn't be started.
+    ToolchainMissing(String),
+    /// Reading the terminal or watching the exercise files failed.
+    Io(String),
+    /// A malformed argument clap couldn't catch, like a contract call.
+    Usage(String),
+    /// A newer change in watch mode superseded the verification.
+    Interrupted,
+}
+
+impl CliError {
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            CliError::NotFound(_) => exit_code::NOT_FOUND,
+            CliError::CompileFailed(_) => exit_code::COMPILE_FAILED,
+            CliError::TestsFailed(_) => exit_code::TESTS_FAILED,
+            CliError::Unfinished(_) => exit_code::UNFINISHED,
+            CliError::Config(_) => exit_code::CONFIG,
+            CliError::ToolchainMissing(_) => exit_code::TOOLCHAIN_MISSING,
+            CliError::Io(_) => exit_code::IO,
+            CliError::Usage(_) => exit_code::USAGE,
+            CliError::Interrupted => exit_code::INTERRUPTED,
+        }
+    }
+
+    /// The name `--format json` reports the error under.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            CliError::NotFound(_) => "not_found",
+            CliError::CompileFailed(_) => "compile_failed",
+            CliError::TestsFailed(_) => "tests_failed",
+            CliError::Unfinished(_) => "unfinished",
+            CliError::Config(_) => "config",
+            CliError::ToolchainMissing(_) => "toolchain_missing",
+            CliError::Io(_) => "io",
+            CliError::Usage(_) => "usage",
+            CliError::Interrupted => "interrupted",
+        }
+    }
+}
+
+impl fmt::Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CliError::NotFound(what) => write!(f, "{} not found", what),
+            CliError::CompileFailed(id) => write!(f, "{} doesn't compile", id),
+            CliError::TestsFailed(id) => write!(f, "{} has failing tests", id),
+            CliError::Unfinished(id) => write!(f, "{} isn't ready to verify", id),
+            CliError::Config(message) => write!(f, "invalid configuration: {}", message),
+            CliError::ToolchainMissing(message) => write!(f, "couldn't run cargo: {}", message),
+            CliError::Io(message) => f.write_str(message),
+            CliError::Usage(message) => f.write_str(message),
+            CliError::Interrupted => f.write_str("interrupted"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn exit_codes_are_distinct() {
+        let errors = [
+            CliError::NotFound(String::new()),
+            CliError::CompileFailed(String::new()),
+            CliError::TestsFailed(String::new()),
+            CliError::Unfinished(String::new()),
+            CliError::Config(String::new()),
+            CliError::ToolchainMissing(String::new()),
+            CliError::Io(String::new()),
+            CliError::Usage(String::new()),
+            CliError::Interrupted,
+        ];
+        let mut codes: Vec<_> = errors.iter().map(CliError::exit_code).collect();
+        codes.sort();
+        codes.dedup();
+        assert_eq!(codes.len(), errors.len());
+        assert!(!codes.contains(&0));
+    }
+}
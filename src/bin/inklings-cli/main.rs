@@ -0,0 +1,865 @@
+use clap::{Parser, Subcommand};
+use colored::*;
+use error::CliError;
+use inklings::backend;
+use inklings::cargo::CancelToken;
+use inklings::curriculum::tier_label;
+use inklings::verify::{Event, FailureDetails, StageResult};
+use inklings::paths::{active_path, next_after, next_incomplete};
+use inklings::progress::ProgressError;
+use inklings::{Curriculum, Exercise, Progress, Verification, Verifier, VerifyOutcome, prereqs, resolve};
+use std::fs;
+use std::path::Path;
+use std::process;
+
+mod batch;
+mod doctor;
+mod error;
+mod paths;
+mod play;
+mod progress;
+mod render;
+mod report;
+mod serve;
+mod terminal;
+mod watch;
+
+#[derive(Parser)]
+#[command(name = "inklings")]
+#[command(about = "Interactive ink! smart contract exercises", long_about = None)]
+#[command(after_help = "Exit codes: 0 success, 1 exercise not finished yet, 2 usage error, 3 not found, \
+4 compile failure, 5 test failure, 6 configuration error, 7 cargo unavailable, 8 I/O failure, 130 interrupted")]
+struct Cli {
+    #[command(subcommand)]
+    command: Option<Commands>,
+    /// Print newline-delimited JSON for tools instead of text (schema in src/bin/inklings-cli/report.rs)
+    #[arg(long, global = true, value_enum, default_value_t)]
+    format: report::Format,
+}
+
+#[derive(Subcommand)]
+enum Commands {
+    /// Verify an exercise, or a whole set of them with --all, --category or --path
+    Verify {
+        #[arg(required_unless_present_any = ["all", "category", "path"])]
+        exercise: Option<String>,
+        /// Verify every exercise
+        #[arg(long, conflicts_with_all = ["exercise", "category", "path"])]
+        all: bool,
+        /// Verify every exercise in a category
+        #[arg(long, conflicts_with_all = ["exercise", "path"])]
+        category: Option<String>,
+        /// Verify every exercise on a learning path
+        #[arg(long, conflicts_with = "exercise")]
+        path: Option<String>,
+        /// How many exercises to verify at once with --all, --category or --path.
+        /// The first job builds in the shared target directory; each extra job
+        /// needs its own, so a cold run compiles the ink! dependencies and takes
+        /// their disk space once per job
+        #[arg(short, long, default_value_t = 1)]
+        jobs: usize,
+        /// Verify even if prerequisites are incomplete
+        #[arg(long)]
+        force: bool,
+        /// Stream cargo's full output instead of a progress spinner
+        #[arg(long)]
+        live: bool,
+        /// Verify again even if nothing changed since the exercise last passed
+        #[arg(long)]
+        fresh: bool,
+    },
+    /// Watch for changes and auto-verify
+    Watch {
+        exercise: Option<String>,
+        /// Stream cargo's full output instead of a progress spinner
+        #[arg(long)]
+        live: bool,
+    },
+    /// Deploy an exercise's contract in-process and call it,
+    /// e.g. `inklings run intro1 "new(true)" flip get`
+    ///
+    /// Arguments are checked against the signatures as written in the
+    /// exercise source, not against a metadata build, so a type alias is
+    /// only known by its name. Events are shown field by field; one with a
+    /// field type that can't be decoded or printed is shown as raw hex.
+    Run {
+        exercise: String,
+        /// A constructor call followed by message calls; lists them if omitted
+        calls: Vec<String>,
+        /// Dev account making the calls
+        #[arg(long, default_value = "alice")]
+        caller: String,
+    },
+    /// Deploy an exercise's contract and call it interactively
+    Play { exercise: String },
+    /// Show hint for an exercise
+    Hint { exercise: String },
+    /// List all exercises
+    List,
+    /// Show your progress
+    Progress {
+        /// First import the progress older versions kept in ~/.inklings_progress.json
+        /// (only before this workspace has progress of its own)
+        #[arg(long)]
+        import: bool,
+    },
+    /// Reset progress
+    Reset,
+    /// Check info/exercises.toml against the exercise tree
+    Doctor,
+    /// Serve JSON-RPC for editor integrations (protocol in src/bin/inklings-cli/serve.rs)
+    Serve {
+        /// Speak JSON-RPC over stdin and stdout
+        #[arg(long, required = true)]
+        stdio: bool,
+    },
+    /// Browse and follow learning paths
+    Path {
+        #[command(subcommand)]
+        command: Option<paths::PathCommand>,
+    },
+}
+
+fn main() {
+    let cli = Cli::parse();
+    // Everything the server passes on from a verification is JSON
+    let serving = matches!(cli.command, Some(Commands::Serve { .. }));
+    report::set_format(if serving { report::Format::Json } else { cli.format });
+    if let Err(e) = run(cli) {
+        if serving {
+            // stdout only carries framed messages, and no request asked for this
+            eprintln!("inklings serve: {}", e);
+        } else if report::is_json() {
+            report::error(&e);
+        }
+        process::exit(e.exit_code());
+    }
+}
+
+fn run(cli: Cli) -> Result<(), CliError> {
+    if !matches!(cli.command, Some(Commands::Doctor)) {
+        doctor::ensure_valid()?;
+    }
+    // These talk to the learner; there's nothing for a tool to read
+    if report::is_json() && matches!(cli.command, None | Some(Commands::Play { .. } | Commands::Reset)) {
+        return Err(CliError::Usage("--format json isn't available for interactive commands".to_string()));
+    }
+
+    match cli.command {
+        Some(Commands::Verify { exercise: Some(exercise), force, live, fresh, .. }) => {
+            verify_exercise(&exercise, VerifyOptions { force, live, fresh }, &CancelToken::default())
+        }
+        Some(Commands::Verify { all, category, path, jobs, fresh, .. }) => {
+            let selection = match (category, path) {
+                (Some(category), _) => batch::Selection::Category(category),
+                (_, Some(path)) => batch::Selection::Path(path),
+                _ if all => batch::Selection::All,
+                _ => unreachable!("clap requires an exercise or a selection"),
+            };
+            batch::run(&load_config()?, selection, jobs, fresh)
+        }
+        Some(Commands::Watch { exercise, live }) => watch::run(exercise, VerifyOptions { live, ..Default::default() }),
+        Some(Commands::Run { exercise, calls, caller }) => run_exercise(&exercise, &calls, &caller),
+        Some(Commands::Play { exercise }) => play_exercise(&exercise),
+        Some(Commands::Hint { exercise }) => show_hint(&exercise),
+        Some(Commands::List) => list_exercises(),
+        Some(Commands::Progress { import }) => {
+            if import {
+                import_progress()?;
+            }
+            show_progress()
+        }
+        Some(Commands::Reset) => reset_progress(),
+        Some(Commands::Doctor) => doctor::run(),
+        Some(Commands::Serve { .. }) => serve::run(load_config()?),
+        Some(Commands::Path { command }) => paths::run(command),
+        None => interactive_mode(),
+    }
+}
+
+/// Loads the manifest, reporting a failure to the user.
+fn load_config() -> Result<Curriculum, CliError> {
+    Curriculum::load().map_err(|e| {
+        if !report::is_json() {
+            println!("{}", format!("Error loading exercises: {}", e).red());
+        }
+        CliError::Config(e.to_string())
+    })
+}
+
+/// Reads the learner's progress, reporting a file that can't be read.
+fn load_progress() -> Result<Progress, CliError> {
+    Progress::load().map_err(progress_error)
+}
+
+/// `Progress::update`, reporting failures like `load_progress`.
+fn update_progress<T>(change: impl FnOnce(&mut Progress) -> T) -> Result<T, CliError> {
+    Progress::update(change).map_err(progress_error)
+}
+
+fn progress_error(e: ProgressError) -> CliError {
+    if !report::is_json() {
+        println!("{}", e.to_string().red());
+    }
+    CliError::Config(e.to_string())
+}
+
+/// Resolves a command-line exercise argument, reporting failures to the user.
+fn resolve_exercise(input: &str) -> Result<Exercise, CliError> {
+    find_exercise(&load_config()?, input)
+}
+
+/// `resolve_exercise` against an already loaded manifest.
+fn find_exercise(config: &Curriculum, input: &str) -> Result<Exercise, CliError> {
+    match resolve::resolve(config, input) {
+        Ok(info) => Ok(info.clone()),
+        Err(err) => {
+            if !report::is_json() {
+                println!("{}", err.to_string().red());
+                if let resolve::ResolveError::NotFound { suggestions, .. } = &err
+                    && !suggestions.is_empty()
+                {
+                    println!("{}", format!("Did you mean: {}?", suggestions.join(", ")).yellow());
+                }
+                println!("{}", "Run 'inklings list' to see available exercises.".yellow());
+            }
+            Err(match err {
+                resolve::ResolveError::NotFound { input, .. } => CliError::NotFound(format!("exercise '{}'", input)),
+                ambiguous @ resolve::ResolveError::Ambiguous { .. } => CliError::Usage(ambiguous.to_string()),
+            })
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+struct VerifyOptions {
+    /// Verify even if prerequisites are incomplete.
+    force: bool,
+    /// Echo cargo's output as it arrives instead of showing a spinner.
+    live: bool,
+    /// Verify even if the source matches the last successful run.
+    fresh: bool,
+}
+
+/// How a verification that didn't fail ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Verified {
+    Passed,
+    /// Skipped: it passed before with the same source.
+    Unchanged,
+}
+
+/// Verifies one exercise, reporting the outcome. A cancelled verification
+/// stops quietly with `CliError::Interrupted`.
+fn verify_exercise(exercise: &str, options: VerifyOptions, cancel: &CancelToken) -> Result<(), CliError> {
+    let config = load_config()?;
+    let info = find_exercise(&config, exercise)?;
+    let result = verify_resolved(&config, &info, options, cancel);
+
+    if report::is_json() {
+        report::emit("verify_finished", verify_finished(&config, &info, &result)?);
+    }
+    result.map(|_| ())
+}
+
+/// The `verify_finished` event for how a verification ended.
+fn verify_finished(
+    config: &Curriculum,
+    info: &Exercise,
+    result: &Result<Verified, CliError>,
+) -> Result<serde_json::Value, CliError> {
+    let (outcome, next) = match result {
+        Ok(Verified::Passed) => {
+            let next = next_after(config, &load_progress()?, &info.id).map(|e| e.id.clone());
+            ("passed", next)
+        }
+        Ok(Verified::Unchanged) => ("unchanged", None),
+        Err(e) => (e.kind(), None),
+    };
+    Ok(serde_json::json!({ "exercise": info.id, "outcome": outcome, "next": next }))
+}
+
+fn verify_resolved(
+    config: &Curriculum,
+    info: &Exercise,
+    options: VerifyOptions,
+    cancel: &CancelToken,
+) -> Result<Verified, CliError> {
+    let json = report::is_json();
+    if json {
+        report::emit("verify_started", serde_json::json!({ "exercise": info.id }));
+    }
+
+    let verifier = Verifier::new(config).force(options.force).fresh(options.fresh);
+    let mut spinner = None;
+    let result = verifier.verify(info, &load_progress()?, cancel, |event| match event {
+        Event::Prerequisites { missing, enforced } => report_prerequisites(info, missing, enforced),
+        Event::Started if !json => println!("{}", format!("🔍 Verifying {}...", info.id).cyan()),
+        Event::Started => {}
+        Event::StageStarted(step) => {
+            spinner = Some(progress::BuildProgress::start(format!("{} {}...", step.running, info.id), options.live));
+        }
+        Event::Output(stream, line) => {
+            if let Some(spinner) = &mut spinner {
+                spinner.line(stream, line);
+            }
+        }
+        Event::StageFinished(stage) => {
+            if let Some(spinner) = spinner.take() {
+                spinner.finish();
+            }
+            report_stage(info, stage);
+        }
+    });
+    let verification = result.map_err(|e| {
+        if !json {
+            println!("{}", format!("Error running cargo: {}", e).red());
+        }
+        CliError::ToolchainMissing(e.to_string())
+    })?;
+
+    update_progress(|progress| progress.record(&info.id, &verification))?;
+    present_outcome(config, info, verification)
+}
+
+/// Prints or emits how one stage went, as soon as it finishes.
+fn report_stage(info: &Exercise, stage: &StageResult) {
+    if report::is_json() {
+        report::emit(
+            "stage_finished",
+            serde_json::json!({
+                "exercise": info.id,
+                "stage": stage.step.subcommand,
+                "success": stage.success,
+                "elapsed_ms": stage.elapsed.as_millis() as u64,
+                "tests": report::score(stage.tests),
+            }),
+        );
+        return;
+    }
+
+    let timing = format!("cargo {} {}", stage.step.subcommand, progress::format_elapsed(stage.elapsed));
+    match stage.tests {
+        _ if !stage.success => println!("{} {}", stage.step.failed.red(), format!("({})", timing).dimmed()),
+        Some(score) => println!(
+            "{} {}",
+            stage.step.passed.green(),
+            format!("({} tests; {})", score.total, timing).dimmed()
+        ),
+        None => println!("{} {}", stage.step.passed.green(), format!("({})", timing).dimmed()),
+    }
+}
+
+/// Presents how a verification ended, mapping anything short of passing to its `CliError`.
+fn present_outcome(config: &Curriculum, info: &Exercise, verification: Verification) -> Result<Verified, CliError> {
+    let json = report::is_json();
+    match verification.outcome {
+        VerifyOutcome::Passed => {
+            if !json {
+                println!("\n{}", "🎉 Exercise completed! Move to the next one.".bright_green().bold());
+                show_completion_stats(config, &info.id)?;
+                suggest_next_exercise(config, &info.id)?;
+            }
+            Ok(Verified::Passed)
+        }
+        VerifyOutcome::Unchanged => {
+            if !json {
+                println!("{}", format!("✓ {} hasn't changed since it last passed.", info.id).green());
+                println!("{}", "Run with --fresh to verify it again anyway.".dimmed());
+            }
+            Ok(Verified::Unchanged)
+        }
+        VerifyOutcome::Locked { .. } => Err(CliError::Unfinished(info.id.clone())),
+        VerifyOutcome::Pending(pending) => {
+            if json {
+                report::pending(&info.id, &pending);
+            } else {
+                render::pending(&info.id, &pending);
+            }
+            Err(CliError::Unfinished(info.id.clone()))
+        }
+        VerifyOutcome::Failed(failure) => {
+            let tests_failed = matches!(failure.details, FailureDetails::Tests(_));
+            let lib_rs = Path::new(&info.path).join("lib.rs");
+            if json {
+                match &failure.details {
+                    FailureDetails::Tests(tests) => report::tests(&info.id, tests),
+                    FailureDetails::Diagnostics(diagnostics) => {
+                        report::diagnostics(&info.id, diagnostics, &lib_rs.display().to_string())
+                    }
+                    FailureDetails::Output(text) => {
+                        report::emit("output", serde_json::json!({ "exercise": info.id, "text": text.trim() }))
+                    }
+                }
+                let hints: Vec<_> = failure.hints.iter().map(String::as_str).collect();
+                report::hints(&info.id, &hints);
+            } else {
+                match &failure.details {
+                    FailureDetails::Tests(tests) => {
+                        println!();
+                        render::tests(tests);
+                    }
+                    FailureDetails::Diagnostics(diagnostics) => {
+                        let source = fs::read_to_string(&lib_rs).unwrap_or_default();
+                        println!();
+                        render::diagnostics(diagnostics, &source, &lib_rs.display().to_string());
+                    }
+                    FailureDetails::Output(text) => println!("{}", text),
+                }
+                let skipped: Vec<_> = failure.skipped.iter().map(|s| format!("cargo {}", s.subcommand)).collect();
+                if !skipped.is_empty() {
+                    println!("{}", format!("Skipped {} until this stage passes.", skipped.join(", ")).dimmed());
+                }
+                println!();
+                if failure.hints.is_empty() {
+                    println!("{}", format!("💡 Try running 'inklings hint {}' for help!", info.id).yellow());
+                } else {
+                    for hint in &failure.hints {
+                        println!("{} {}", "💡".yellow(), hint.yellow());
+                    }
+                }
+            }
+            Err(if tests_failed {
+                CliError::TestsFailed(info.id.clone())
+            } else {
+                CliError::CompileFailed(info.id.clone())
+            })
+        }
+        VerifyOutcome::Cancelled => Err(CliError::Interrupted),
+    }
+}
+
+/// Warns about incomplete prerequisites, and says how to go ahead if they're enforced.
+fn report_prerequisites(info: &Exercise, missing: &[String], enforced: bool) {
+    if report::is_json() {
+        report::emit(
+            "prerequisites",
+            serde_json::json!({ "exercise": info.id, "missing": missing, "enforced": enforced }),
+        );
+        return;
+    }
+
+    let message = format!("🔒 {} builds on exercises you haven't completed yet:", info.id);
+    if enforced {
+        println!("{}", message.red().bold());
+    } else {
+        println!("{}", message.yellow());
+    }
+    for id in missing {
+        println!("   - {}", id.bright_blue());
+    }
+    println!();
+
+    if enforced {
+        println!("{}", "Complete them first, or run with --force to verify anyway.".yellow());
+    }
+}
+
+fn show_completion_stats(config: &Curriculum, current_exercise: &str) -> Result<(), CliError> {
+    let progress = load_progress()?;
+    let total = config.exercises.len();
+    let completed = progress.completed.len();
+    let percentage = (completed as f32 / total as f32 * 100.0) as u32;
+    
+    let attempts = progress.attempts.get(current_exercise).unwrap_or(&0);
+    
+    println!();
+    println!("{}", "📊 Your Progress:".cyan().bold());
+    println!("   Completed: {}/{} ({}%)", completed, total, percentage);
+    println!("   This exercise took {} attempt(s)", attempts);
+
+    let hints = progress.hints_revealed.get(current_exercise).copied().unwrap_or(0);
+    if hints > 0 {
+        println!("   You used {} hint(s)", hints);
+    }
+    Ok(())
+}
+
+fn suggest_next_exercise(config: &Curriculum, current_id: &str) -> Result<(), CliError> {
+    let progress = load_progress()?;
+    if let Some(next) = next_after(config, &progress, current_id) {
+        println!();
+        println!("{}", "📌 Next exercise:".cyan());
+        println!("   {} - {}", next.id.bright_blue(), next.name);
+        println!();
+        println!("   Run: {}", format!("inklings verify {}", next.id).green());
+        return Ok(());
+    }
+
+    println!();
+    match active_path(config, &progress) {
+        Some((name, _)) => {
+            println!("{}", format!("🏆 Congratulations! You've completed the {} path!", name).bright_green().bold());
+            println!("{}", "Run 'inklings path list' to pick your next path.".green());
+        }
+        None => {
+            println!("{}", "🏆 Congratulations! You've completed all exercises!".bright_green().bold());
+        }
+    }
+    Ok(())
+}
+
+fn run_exercise(exercise: &str, calls: &[String], caller: &str) -> Result<(), CliError> {
+    let config = load_config()?;
+    let info = find_exercise(&config, exercise)?;
+    play::run(&*backend::configured(&config), &config.target_dir(), &info, calls, caller)
+}
+
+fn play_exercise(exercise: &str) -> Result<(), CliError> {
+    let config = load_config()?;
+    play::play(&*backend::configured(&config), &config.target_dir(), &find_exercise(&config, exercise)?)
+}
+
+fn show_hint(exercise: &str) -> Result<(), CliError> {
+    let info = resolve_exercise(exercise)?;
+    if report::is_json() {
+        report::emit("hint", reveal_hint(&info)?);
+        return Ok(());
+    }
+    println!("{}", format!("Hint for {}:", info.id).cyan().bold());
+    println!();
+
+    let progress = load_progress()?;
+    let attempts = progress.attempts.get(&info.id).unwrap_or(&0);
+
+    if *attempts > 0 {
+        println!("{}", format!("(You've attempted this {} time(s))", attempts).dimmed());
+        println!();
+    }
+
+    println!("{}", info.name.bright_white().bold());
+    println!("{}", format!("Graded by: {} ({})", info.mode, info.mode.description()).dimmed());
+    println!();
+
+    let tiers = info.hint_tiers();
+    if tiers.is_empty() {
+        println!("{}", "There are no hints for this exercise yet.".yellow());
+        return Ok(());
+    }
+
+    let shown = reveal_tier(&info.id, tiers.len())?;
+
+    let index = shown - 1;
+    println!(
+        "{}",
+        format!("Hint {}/{} ({})", shown, tiers.len(), tier_label(index, tiers.len())).yellow().bold()
+    );
+    println!("{}", tiers[index]);
+
+    if shown < tiers.len() {
+        println!(
+            "{}",
+            format!(
+                "Still stuck? Run 'inklings hint {}' again for the {}.",
+                info.id,
+                tier_label(shown, tiers.len())
+            )
+            .dimmed()
+        );
+    } else if tiers.len() > 1 {
+        println!("{}", "That's the last hint for this exercise.".dimmed());
+    }
+    Ok(())
+}
+
+/// `show_hint` for `--format json`: reveals a tier the same way and
+/// returns the `hint` object.
+fn reveal_hint(info: &Exercise) -> Result<serde_json::Value, CliError> {
+    let tiers = info.hint_tiers();
+    if tiers.is_empty() {
+        return Ok(serde_json::json!({ "exercise": info.id, "shown": 0, "tiers": 0, "label": null, "text": null }));
+    }
+
+    let shown = reveal_tier(&info.id, tiers.len())?;
+    Ok(serde_json::json!({
+        "exercise": info.id,
+        "shown": shown,
+        "tiers": tiers.len(),
+        "label": tier_label(shown - 1, tiers.len()),
+        "text": tiers[shown - 1],
+    }))
+}
+
+/// Reveals one more of an exercise's `tiers` hint tiers, stopping at the
+/// last. Returns how many are now revealed.
+fn reveal_tier(id: &str, tiers: usize) -> Result<usize, CliError> {
+    update_progress(|progress| {
+        let revealed = progress.hints_revealed.get(id).copied().unwrap_or(0);
+        let shown = (revealed + 1).min(tiers);
+        progress.hints_revealed.insert(id.to_string(), shown);
+        shown
+    })
+}
+
+fn list_exercises() -> Result<(), CliError> {
+    if report::is_json() {
+        report::emit("exercises", report::exercises(&load_config()?, &load_progress()?));
+        return Ok(());
+    }
+    println!("{}", "Available exercises:".cyan().bold());
+    println!();
+    
+    let progress = load_progress()?;
+    
+    match Curriculum::load() {
+        Ok(config) => {
+            if !config.categories.is_empty() {
+                for (category, entry) in &config.categories {
+                    println!("{}", format!("  {}", category).yellow().bold());
+                    for id in &entry.exercises {
+                        if let Some(pos) = config.exercises.iter().position(|e| &e.id == id) {
+                            print!("  ");
+                            print_list_entry(&config, &progress, pos);
+                        }
+                    }
+                    println!();
+                }
+            } else {
+                for pos in 0..config.exercises.len() {
+                    print_list_entry(&config, &progress, pos);
+                }
+            }
+            
+            let total = config.exercises.len();
+            let completed = progress.completed.len();
+            let percentage = if total > 0 {
+                (completed as f32 / total as f32 * 100.0) as u32
+            } else {
+                0
+            };
+            
+            println!("{}", format!("Progress: {}/{} ({}%)", completed, total, percentage).cyan());
+            println!();
+            println!("{}", "Run 'inklings verify <exercise>' to start! (id, name like 'intro1', or number)".green());
+            println!("{}", "Run 'inklings hint <exercise>' for help.".green());
+            println!("{}", "Run 'inklings progress' for detailed stats.".green());
+        }
+        Err(e) => {
+            println!("{}", format!("Error loading exercises: {}", e).red());
+            return Err(CliError::Config(e.to_string()));
+        }
+    }
+    Ok(())
+}
+
+/// One `inklings list` line: status, position, id and name, plus what a locked exercise waits on.
+fn print_list_entry(config: &Curriculum, progress: &Progress, pos: usize) {
+    let info = &config.exercises[pos];
+    let missing = prereqs::missing(config, progress, &info.id);
+    let status = if progress.completed.contains(&info.id) {
+        "✓".green().bold()
+    } else if missing.is_empty() {
+        "○".dimmed()
+    } else {
+        "🔒".normal()
+    };
+
+    print!("  {} {:>2}. {} - {}", status, pos + 1, info.id.bright_blue(), info.name);
+    if !progress.completed.contains(&info.id) && !missing.is_empty() {
+        print!("{}", format!(" (needs {})", missing.join(", ")).dimmed());
+    }
+    println!();
+}
+
+fn show_progress() -> Result<(), CliError> {
+    if report::is_json() {
+        report::emit("progress", report::progress(&load_config()?, &load_progress()?));
+        return Ok(());
+    }
+    let progress = load_progress()?;
+    
+    println!("{}", "📊 Your Inklings Progress".cyan().bold());
+    println!();
+    if !Progress::file().exists()
+        && let Some(legacy) = Progress::legacy_file().filter(|legacy| legacy.exists())
+    {
+        println!("{}", format!("Found progress from an older inklings in {}.", legacy.display()).yellow());
+        println!("{}", "Run 'inklings progress --import' to carry it over to this workspace.".yellow());
+        println!();
+    }
+    
+    match Curriculum::load() {
+        Ok(config) => {
+            let total = config.exercises.len();
+            let completed = progress.completed.len();
+            let percentage = if total > 0 {
+                (completed as f32 / total as f32 * 100.0) as u32
+            } else {
+                0
+            };
+            
+            println!("{}", format!("Overall: {}/{} exercises completed ({}%)", completed, total, percentage).bright_white().bold());
+            println!();
+            
+            // Progress bar
+            let bar_width = 50;
+            let filled = (bar_width as f32 * completed as f32 / total as f32) as usize;
+            let bar: String = "█".repeat(filled) + &"░".repeat(bar_width - filled);
+            println!("[{}] {}%", bar.green(), percentage);
+            println!();
+            
+            // Category breakdown
+            if !config.categories.is_empty() {
+                println!("{}", "By Category:".yellow().bold());
+                for (category, entry) in &config.categories {
+                    let exercise_ids = &entry.exercises;
+                    let category_completed = exercise_ids.iter()
+                        .filter(|id| progress.completed.contains(*id))
+                        .count();
+                    let category_total = exercise_ids.len();
+                    let cat_percentage = if category_total > 0 {
+                        (category_completed as f32 / category_total as f32 * 100.0) as u32
+                    } else {
+                        0
+                    };
+                    
+                    println!("  {}: {}/{} ({}%)", 
+                        category.bright_blue(), 
+                        category_completed, 
+                        category_total, 
+                        cat_percentage
+                    );
+                }
+                println!();
+            }
+            
+            // Hint usage
+            if !progress.hints_revealed.is_empty() {
+                println!("{}", "Hints Used:".yellow().bold());
+                for info in &config.exercises {
+                    if let Some(revealed) = progress.hints_revealed.get(&info.id) {
+                        println!(
+                            "  {} - {}/{} hint(s)",
+                            info.id.bright_blue(),
+                            revealed,
+                            info.hint_tiers().len()
+                        );
+                    }
+                }
+                println!();
+            }
+
+            // Partial credit on unfinished test exercises
+            let partial: Vec<_> = config
+                .exercises
+                .iter()
+                .filter(|e| !progress.completed.contains(&e.id))
+                .filter_map(|e| progress.test_scores.get(&e.id).map(|score| (e, score)))
+                .collect();
+            if !partial.is_empty() {
+                println!("{}", "Tests Passing (In Progress):".yellow().bold());
+                for (info, score) in partial {
+                    println!("  {} - {}/{} tests", info.id.bright_blue(), score.passed, score.total);
+                }
+                println!();
+            }
+
+            // Dependency graph
+            if config.exercises.iter().any(|e| !prereqs::requirements(&config, &e.id).is_empty()) {
+                println!("{}", "Prerequisites:".yellow().bold());
+                render::prerequisite_graph(&config, &progress);
+                println!();
+            }
+
+            // Most attempted
+            if !progress.attempts.is_empty() {
+                println!("{}", "Most Challenging:".yellow().bold());
+                let mut attempts_vec: Vec<_> = progress.attempts.iter().collect();
+                attempts_vec.sort_by(|a, b| b.1.cmp(a.1));
+                
+                for (exercise_id, count) in attempts_vec.iter().take(3) {
+                    if let Some(info) = config.exercises.iter().find(|e| &e.id == *exercise_id) {
+                        println!("  {} - {} attempt(s)", info.name, count);
+                    }
+                }
+                println!();
+            }
+            
+            // Last worked on
+            if let Some(last) = &progress.last_worked_on
+                && let Some(info) = config.exercises.iter().find(|e| &e.id == last)
+            {
+                println!("{}", "Last Worked On:".yellow().bold());
+                println!("  {} - {}", info.id.bright_blue(), info.name);
+
+                if !progress.completed.contains(last) {
+                    println!();
+                    println!("{}", "Continue with:".green());
+                    println!("  {}", format!("inklings verify {}", last).green());
+                }
+            }
+        }
+        Err(e) => {
+            println!("{}", format!("Error loading exercises: {}", e).red());
+            return Err(CliError::Config(e.to_string()));
+        }
+    }
+    Ok(())
+}
+
+/// `inklings progress --import`: the one-time copy of the legacy progress file.
+fn import_progress() -> Result<(), CliError> {
+    let progress = Progress::import_legacy().map_err(progress_error)?;
+    if !report::is_json() {
+        println!("{}", format!("✓ Imported {} completed exercise(s).", progress.completed.len()).green());
+        println!();
+    }
+    Ok(())
+}
+
+fn reset_progress() -> Result<(), CliError> {
+    println!("{}", "⚠️  Are you sure you want to reset all progress? (y/N)".yellow().bold());
+    
+    let mut input = String::new();
+    std::io::stdin()
+        .read_line(&mut input)
+        .map_err(|e| CliError::Io(format!("couldn't read the answer: {}", e)))?;
+    
+    if input.trim().to_lowercase() == "y" {
+        Progress::reset().map_err(progress_error)?;
+        println!("{}", "✓ Progress reset successfully!".green());
+    } else {
+        println!("{}", "Cancelled.".dimmed());
+    }
+    Ok(())
+}
+
+fn interactive_mode() -> Result<(), CliError> {
+    println!("{}", r#"
+  _       _    _ _                 
+ (_)_ __ | | _| (_)_ __   __ _ ___ 
+ | | '_ \| |/ / | | '_ \ / _` / __|
+ | | | | |   <| | | | | | (_| \__ \
+ |_|_| |_|_|\_\_|_|_| |_|\__, |___/
+                         |___/     
+"#.bright_cyan());
+
+    println!("{}", "Interactive ink! smart contract exercises\n".cyan());
+
+    let progress = load_progress()?;
+
+    if let Some(last) = &progress.last_worked_on {
+        println!("{}", format!("Welcome back! Last worked on: {}", last).yellow());
+        println!();
+    }
+
+    if let Ok(config) = Curriculum::load() {
+        if let Some((name, ids)) = active_path(&config, &progress) {
+            let completed = ids.iter().filter(|id| progress.completed.contains(*id)).count();
+            println!("{}", format!("Following the {} path ({}/{} completed)", name, completed, ids.len()).cyan());
+        }
+        if let Some(next) = next_incomplete(&config, &progress) {
+            println!("{}", format!("Next up: {} - {}", next.id, next.name).green());
+        }
+        println!();
+    }
+
+    println!("Commands:");
+    println!("  {} - List all exercises with completion status", "inklings list".green());
+    println!("  {} - View your progress statistics", "inklings progress".green());
+    println!("  {} - Verify your solution", "inklings verify <exercise>".green());
+    println!("  {} - Get a hint", "inklings hint <exercise>".green());
+    println!("  {} - Watch mode (auto-verify)", "inklings watch <exercise>".green());
+    println!("  {} - Choose a learning path", "inklings path".green());
+    Ok(())
+}
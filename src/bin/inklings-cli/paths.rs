@@ -0,0 +1,168 @@
+use crate::error::CliError;
+use crate::{load_config, load_progress, report, update_progress};
+use clap::Subcommand;
+use colored::*;
+use inklings::paths::completed_in;
+use inklings::{Curriculum, Progress};
+
+#[derive(Subcommand)]
+pub enum PathCommand {
+    /// List learning paths and your progress in each
+    List,
+    /// Follow a learning path
+    Select { name: String },
+    /// Show progress within a learning path (defaults to the active one)
+    Show { name: Option<String> },
+    /// Stop following a learning path and go back to manifest order
+    Clear,
+}
+
+pub fn run(command: Option<PathCommand>) -> Result<(), CliError> {
+    let config = load_config()?;
+
+    match command.unwrap_or(PathCommand::List) {
+        PathCommand::List => list_paths(&config)?,
+        PathCommand::Select { name } => return select_path(&config, &name),
+        PathCommand::Show { name } => return show_path(&config, name.as_deref()),
+        PathCommand::Clear => {
+            update_progress(|progress| progress.active_path = None)?;
+            let progress = load_progress()?;
+            if report::is_json() {
+                report_path(&config, &progress, None);
+            } else {
+                println!("{}", "✓ No longer following a learning path.".green());
+            }
+        }
+    }
+    Ok(())
+}
+
+fn list_paths(config: &Curriculum) -> Result<(), CliError> {
+    let progress = load_progress()?;
+    if report::is_json() {
+        let paths: Vec<_> = config
+            .learning_paths
+            .iter()
+            .map(|(name, ids)| {
+                serde_json::json!({
+                    "name": name,
+                    "completed": completed_in(ids, &progress),
+                    "total": ids.len(),
+                    "active": progress.active_path.as_deref() == Some(name.as_str()),
+                })
+            })
+            .collect();
+        report::emit("paths", serde_json::json!({ "paths": paths }));
+        return Ok(());
+    }
+
+    println!("{}", "Learning paths:".cyan().bold());
+    println!();
+
+    if config.learning_paths.is_empty() {
+        println!("{}", "  No learning paths are defined in info/exercises.toml.".dimmed());
+        return Ok(());
+    }
+
+    for (name, ids) in &config.learning_paths {
+        let marker = if progress.active_path.as_deref() == Some(name.as_str()) {
+            "▶".green().bold()
+        } else {
+            " ".normal()
+        };
+        println!(
+            "  {} {} - {}/{} completed",
+            marker,
+            name.bright_blue(),
+            completed_in(ids, &progress),
+            ids.len()
+        );
+    }
+
+    println!();
+    println!("{}", "Run 'inklings path select <name>' to follow a path.".green());
+    Ok(())
+}
+
+fn select_path(config: &Curriculum, name: &str) -> Result<(), CliError> {
+    if !config.learning_paths.contains_key(name) {
+        if !report::is_json() {
+            let known: Vec<_> = config.learning_paths.keys().map(String::as_str).collect();
+            println!("{}", format!("Learning path '{}' not found!", name).red());
+            println!("{}", format!("Available paths: {}", known.join(", ")).yellow());
+        }
+        return Err(CliError::NotFound(format!("learning path '{}'", name)));
+    }
+
+    update_progress(|progress| progress.active_path = Some(name.to_string()))?;
+
+    if !report::is_json() {
+        println!("{}", format!("✓ Now following the {} path.", name).green());
+        println!();
+    }
+    show_path(config, Some(name))
+}
+
+fn show_path(config: &Curriculum, name: Option<&str>) -> Result<(), CliError> {
+    let progress = load_progress()?;
+    if report::is_json() {
+        let name = name.or(progress.active_path.as_deref());
+        if let Some(name) = name
+            && !config.learning_paths.contains_key(name)
+        {
+            return Err(CliError::NotFound(format!("learning path '{}'", name)));
+        }
+        report_path(config, &progress, name);
+        return Ok(());
+    }
+
+    let name = match name.or(progress.active_path.as_deref()) {
+        Some(name) => name,
+        None => {
+            println!("{}", "You are not following a learning path.".yellow());
+            println!("{}", "Run 'inklings path list' to see the available paths.".yellow());
+            return Ok(());
+        }
+    };
+
+    let Some(ids) = config.learning_paths.get(name) else {
+        println!("{}", format!("Learning path '{}' not found!", name).red());
+        return Err(CliError::NotFound(format!("learning path '{}'", name)));
+    };
+
+    let completed = completed_in(ids, &progress);
+    println!("{}", format!("📚 {} path: {}/{} completed", name, completed, ids.len()).cyan().bold());
+    println!();
+
+    let mut next = None;
+    for id in ids {
+        let Some(info) = config.exercises.iter().find(|e| &e.id == id) else {
+            continue;
+        };
+        let status = if progress.completed.contains(id) {
+            "✓".green().bold()
+        } else {
+            next.get_or_insert(info);
+            "○".dimmed()
+        };
+        println!("  {} {} - {}", status, info.id.bright_blue(), info.name);
+    }
+
+    println!();
+    match next {
+        Some(info) => println!("   Next: {}", format!("inklings verify {}", info.id).green()),
+        None => println!("{}", format!("🏆 You've completed the {} path!", name).bright_green().bold()),
+    }
+    Ok(())
+}
+
+/// The `path` object for `--format json`; `name` is None when no path is followed.
+fn report_path(config: &Curriculum, progress: &Progress, name: Option<&str>) {
+    let ids = name.and_then(|name| config.learning_paths.get(name)).map_or(&[][..], Vec::as_slice);
+    let exercises: Vec<_> = ids
+        .iter()
+        .map(|id| serde_json::json!({ "id": id, "completed": progress.completed.contains(id) }))
+        .collect();
+    let next = ids.iter().find(|id| !progress.completed.contains(*id));
+    report::emit("path", serde_json::json!({ "name": name, "exercises": exercises, "next": next }));
+}
@@ -0,0 +1,514 @@
+//! `inklings run` and `inklings play`: calling an exercise's contract by hand.
+
+use crate::error::CliError;
+use crate::progress::BuildProgress;
+use crate::{render, report};
+use inklings::{Backend, CancelToken, Exercise};
+use inklings::contract::{self, ContractInfo};
+use inklings::sandbox::{self, Call, Failure, Request, Response, Session};
+use colored::*;
+use rustyline::completion::{Completer, Pair};
+use rustyline::error::ReadlineError;
+use rustyline::highlight::Highlighter;
+use rustyline::hint::Hinter;
+use rustyline::validate::Validator;
+use rustyline::{Context, Editor, Helper};
+use std::fs;
+use std::path::Path;
+
+const COMMANDS: [(&str, &str, &str); 8] = [
+    (".as", "<account>", "make the next calls from alice, bob, charlie, django, eve or frank"),
+    (".value", "<amount>", "transfer value with the next call (payable messages only)"),
+    (".advance", "[n]", "advance the chain by n blocks (default 1)"),
+    (".timestamp", "<ms>", "set the block timestamp"),
+    (".events", "", "list every event emitted so far"),
+    (".storage", "", "show the contract's storage"),
+    (".help", "", "show the contract's interface and these commands"),
+    (".quit", "", "leave the playground"),
+];
+
+/// Reads the exercise's source and interface, reporting why it can't be run if it can't.
+fn load(info: &Exercise) -> Result<(ContractInfo, String), CliError> {
+    let source = fs::read_to_string(Path::new(&info.path).join("lib.rs")).unwrap_or_default();
+    match contract::parse(&source) {
+        Some(contract) => Ok((contract, source)),
+        None => {
+            if !report::is_json() {
+                println!("{}", format!("{} doesn't contain an #[ink::contract] module yet.", info.id).red());
+            }
+            Err(CliError::Unfinished(info.id.clone()))
+        }
+    }
+}
+
+fn start(
+    backend: &dyn Backend,
+    target_dir: &Path,
+    info: &Exercise,
+    contract: &ContractInfo,
+    source: &str,
+) -> Result<Session, CliError> {
+    if !report::is_json() {
+        println!("{}", format!("🔨 Building {} for the off-chain engine...", info.id).cyan());
+    }
+    let mut spinner = BuildProgress::start(format!("Building the driver for {}...", info.id), false);
+    let on_line = &mut |stream, line: &str| spinner.line(stream, line);
+    let started = Session::start(backend, target_dir, Path::new(&info.path), contract, source, &CancelToken::default(), on_line);
+    spinner.finish();
+    started.map_err(|failure| {
+        let error = match &failure {
+            Failure::Invalid(message) => CliError::Usage(message.clone()),
+            Failure::Compile { .. } => CliError::CompileFailed(info.id.clone()),
+            Failure::Cargo(message) => CliError::ToolchainMissing(message.clone()),
+            Failure::Cancelled => CliError::Interrupted,
+        };
+        if report::is_json() {
+            report_failure(info, failure);
+        } else {
+            print_failure(Path::new(&info.path), failure);
+        }
+        error
+    })
+}
+
+/// `inklings run`: deploys with the first call and makes the rest. A call
+/// that panics or returns an error ends the run without failing the command;
+/// one the contract can't accept (unknown name, wrong number or malformed
+/// arguments) fails it as a usage error, before building where possible.
+pub fn run(
+    backend: &dyn Backend,
+    target_dir: &Path,
+    info: &Exercise,
+    calls: &[String],
+    caller: &str,
+) -> Result<(), CliError> {
+    let (contract, source) = load(info)?;
+    let json = report::is_json();
+    if calls.is_empty() {
+        if json {
+            report_interface(&contract);
+        } else {
+            print_interface(&info.id, &contract);
+        }
+        return Ok(());
+    }
+
+    let calls: Result<Vec<_>, _> = calls.iter().map(|c| Call::parse(c)).collect();
+    let calls = calls.map_err(|e| {
+        if !json {
+            println!("{}", e.red());
+        }
+        CliError::Usage(e)
+    })?;
+    let mut requests = vec![(None, Request::Caller(caller.to_string()))];
+    for (i, call) in calls.into_iter().enumerate() {
+        let request = match i {
+            0 => Request::Deploy { call: call.clone(), value: 0 },
+            _ => Request::Call { call: call.clone(), value: 0 },
+        };
+        requests.push((Some(call), request));
+    }
+    for (call, request) in &requests {
+        if let Err(e) = sandbox::check(&contract, request, true) {
+            let e = match call {
+                Some(call) => format!("{}: {}", call, e),
+                None => e,
+            };
+            if !json {
+                println!("{}", e.red());
+            }
+            return Err(CliError::Usage(e));
+        }
+    }
+
+    let mut session = start(backend, target_dir, info, &contract, &source)?;
+    let mut storage = Vec::new();
+
+    for (call, request) in &requests {
+        if let Some(call) = call
+            && !json
+        {
+            println!();
+            println!("{} {} {}", "▶".cyan(), call.to_string().bold(), format!("as {}", caller).dimmed());
+        }
+        let response = session.send(request).map_err(|e| {
+            if !json {
+                println!("{}", e.red());
+            }
+            CliError::Usage(e)
+        })?;
+        let completed = if json {
+            report_response(&contract, call.as_ref(), &response)
+        } else {
+            print_response(&contract, call.as_ref(), &response, &mut storage)
+        };
+        // The driver turned the request down, e.g. an argument it couldn't parse
+        if let Some(error) = response.error {
+            return Err(CliError::Usage(error));
+        }
+        if !completed {
+            if !json {
+                println!("\n{}", "The run stopped before all calls were made.".yellow());
+            }
+            return Ok(());
+        }
+    }
+    Ok(())
+}
+
+/// Lists the constructors, messages and events the contract exposes.
+pub fn print_interface(id: &str, contract: &ContractInfo) {
+    println!("{}", format!("📜 {} ({})", contract.storage, id).cyan().bold());
+
+    println!("\n{}", "Constructors:".bold());
+    for constructor in &contract.constructors {
+        let payable = if constructor.payable { "[payable]" } else { "" };
+        println!("   {} {}", constructor.to_string().bright_blue(), payable.dimmed());
+    }
+    println!("\n{}", "Messages:".bold());
+    for message in &contract.messages {
+        let mut tags = Vec::new();
+        if message.mutates {
+            tags.push("mut");
+        }
+        if message.payable {
+            tags.push("payable");
+        }
+        let tags = if tags.is_empty() { String::new() } else { format!("[{}]", tags.join(", ")) };
+        println!("   {} {}", message.to_string().bright_blue(), tags.dimmed());
+    }
+    if !contract.events.is_empty() {
+        println!("\n{}", "Events:".bold());
+        for event in &contract.events {
+            println!("   {}", event.name);
+        }
+    }
+
+    let Some(constructor) = contract.constructors.first() else {
+        return;
+    };
+    let example: Vec<_> = constructor.params.iter().map(|p| p.name.as_str()).collect();
+    println!();
+    println!(
+        "Run: {}",
+        format!("inklings run {} \"{}({})\" <message>...", id, constructor.name, example.join(", ")).green()
+    );
+    println!("     {}", format!("inklings play {}", id).green());
+    println!(
+        "{}",
+        format!("Accounts are named {}; numbers, true/false and strings are passed as-is.", sandbox::DEV_ACCOUNTS.join(", "))
+            .dimmed()
+    );
+}
+
+/// Prints what a request returned, emitted and changed in storage, updating
+/// `storage` to the new field values. Returns false if the request failed.
+pub fn print_response(contract: &ContractInfo, call: Option<&Call>, response: &Response, storage: &mut Vec<(String, String)>) -> bool {
+    if let Some(error) = &response.error {
+        println!("   {} {}", "✗".red(), error.red());
+        return false;
+    }
+    if let Some(message) = &response.panic {
+        println!("   {} {}", "panicked:".red().bold(), message.red());
+        println!("   {}", "On-chain the call would trap and its changes would be reverted.".dimmed());
+        return false;
+    }
+
+    let returns = call.and_then(|c| contract.message(&c.name)).is_some_and(|m| m.returns.is_some());
+    if let Some(value) = response.returned.as_ref().filter(|_| returns) {
+        println!("   {} {}", "returned".green(), value);
+        if value.starts_with("Err(") {
+            println!("   {}", "On-chain an Err return reverts the call's storage changes.".dimmed());
+        }
+    }
+    for event in &response.events {
+        println!("   {} {}", "event".magenta(), event);
+    }
+    if !response.fields.is_empty() {
+        for (name, value) in &response.fields {
+            match storage.iter().find(|(n, _)| n == name) {
+                Some((_, old)) if old == value => {}
+                Some((_, old)) => println!("   {} {}: {} → {}", "storage".yellow(), name, old.dimmed(), value),
+                None => println!("   {} {} = {}", "storage".yellow(), name, value),
+            }
+        }
+        *storage = response.fields.clone();
+    }
+    if let Some((number, timestamp)) = response.block {
+        println!("   {} #{}, timestamp {}", "block".blue(), number, timestamp);
+    }
+    true
+}
+
+/// `print_interface` for `--format json`.
+fn report_interface(contract: &ContractInfo) {
+    let callables = |callables: &[contract::Callable]| -> Vec<_> {
+        callables
+            .iter()
+            .map(|c| {
+                let params: Vec<_> = c.params.iter().map(|p| serde_json::json!({ "name": p.name, "type": p.ty })).collect();
+                serde_json::json!({
+                    "name": c.name,
+                    "params": params,
+                    "returns": c.returns,
+                    "mutates": c.mutates,
+                    "payable": c.payable,
+                })
+            })
+            .collect()
+    };
+    report::emit(
+        "interface",
+        serde_json::json!({
+            "constructors": callables(&contract.constructors),
+            "messages": callables(&contract.messages),
+            "events": contract.events.iter().map(|e| &e.name).collect::<Vec<_>>(),
+        }),
+    );
+}
+
+/// `print_response` for `--format json`. The caller switch before the
+/// first call is only reported if it failed.
+fn report_response(contract: &ContractInfo, call: Option<&Call>, response: &Response) -> bool {
+    let failed = response.error.is_some() || response.panic.is_some();
+    if call.is_none() && !failed {
+        return true;
+    }
+    let returns = call.and_then(|c| contract.message(&c.name)).is_some_and(|m| m.returns.is_some());
+    let storage: serde_json::Map<_, _> =
+        response.fields.iter().map(|(name, value)| (name.clone(), serde_json::json!(value))).collect();
+    report::emit(
+        "call",
+        serde_json::json!({
+            "call": call.map(|c| c.to_string()),
+            "returned": response.returned.as_ref().filter(|_| returns),
+            "panic": response.panic,
+            "error": response.error,
+            "events": response.events,
+            "storage": storage,
+            "block": response.block.map(|(number, timestamp)| serde_json::json!({ "number": number, "timestamp": timestamp })),
+        }),
+    );
+    !failed
+}
+
+/// `print_failure` for `--format json`: the exercise's own compile errors.
+/// Anything else is left to the final `error` object.
+fn report_failure(info: &Exercise, failure: Failure) {
+    if let Failure::Compile { diagnostics, exercise_lines, .. } = failure {
+        let exercise: Vec<_> = diagnostics
+            .into_iter()
+            .filter(|d| d.primary_span().is_some_and(|s| s.line_start <= exercise_lines))
+            .collect();
+        report::diagnostics(&info.id, &exercise, &Path::new(&info.path).join("lib.rs").display().to_string());
+    }
+}
+
+pub fn print_failure(exercise_path: &Path, failure: Failure) {
+    match failure {
+        Failure::Invalid(message) => println!("{}", message.red()),
+        Failure::Cancelled => {}
+        Failure::Cargo(message) => {
+            println!("{}", "❌ Couldn't run the contract:".red());
+            println!("{}", message);
+        }
+        Failure::Compile { diagnostics, source, exercise_lines } => {
+            let (exercise, driver): (Vec<_>, Vec<_>) = diagnostics
+                .into_iter()
+                .partition(|d| d.primary_span().is_some_and(|s| s.line_start <= exercise_lines));
+            if !exercise.is_empty() {
+                println!("{}", "❌ The exercise doesn't compile yet:".red());
+                println!();
+                render::diagnostics(&exercise, &source, &exercise_path.join("lib.rs").display().to_string());
+            }
+            if !driver.is_empty() {
+                println!("{}", "❌ inklings couldn't drive this contract:".red());
+                println!();
+                render::diagnostics(&driver, &source, "inklings driver");
+            }
+        }
+    }
+}
+
+/// Tab completion for message, constructor, command and account names.
+struct PlayHelper {
+    words: Vec<String>,
+}
+
+impl Completer for PlayHelper {
+    type Candidate = Pair;
+
+    fn complete(&self, line: &str, pos: usize, _ctx: &Context<'_>) -> rustyline::Result<(usize, Vec<Pair>)> {
+        let before = &line[..pos];
+        let start = before.rfind(|c: char| c.is_whitespace() || c == '(' || c == ',').map_or(0, |i| i + 1);
+        let word = &before[start..];
+
+        // The first word is a call or a command; later words are arguments
+        let candidates: Vec<String> = if start == 0 {
+            self.words.clone()
+        } else {
+            sandbox::DEV_ACCOUNTS.iter().map(|a| a.to_string()).collect()
+        };
+        let pairs = candidates
+            .into_iter()
+            .filter(|c| c.starts_with(word))
+            .map(|c| Pair { display: c.clone(), replacement: c })
+            .collect();
+        Ok((start, pairs))
+    }
+}
+
+impl Hinter for PlayHelper {
+    type Hint = String;
+}
+impl Highlighter for PlayHelper {}
+impl Validator for PlayHelper {}
+impl Helper for PlayHelper {}
+
+fn print_commands() {
+    println!("\n{}", "Commands:".bold());
+    for (command, args, help) in COMMANDS {
+        println!("   {} {:<10} {}", format!("{:<10}", command).bright_blue(), args, help.dimmed());
+    }
+    println!("{}", "Call a constructor to (re)deploy, e.g. new(100), then call messages, e.g. transfer(bob, 10).".dimmed());
+}
+
+/// An interactive session with one deployed instance of the exercise's contract.
+pub fn play(backend: &dyn Backend, target_dir: &Path, info: &Exercise) -> Result<(), CliError> {
+    let (contract, source) = load(info)?;
+    let mut session = start(backend, target_dir, info, &contract, &source)?;
+
+    let mut words: Vec<String> = contract.constructors.iter().chain(&contract.messages).map(|c| c.name.clone()).collect();
+    words.extend(COMMANDS.iter().map(|(c, _, _)| c.to_string()));
+    let mut editor = match Editor::new() {
+        Ok(editor) => editor,
+        Err(e) => {
+            println!("{}", format!("Couldn't start the playground: {}", e).red());
+            return Ok(());
+        }
+    };
+    editor.set_helper(Some(PlayHelper { words }));
+
+    println!();
+    print_interface(&info.id, &contract);
+    print_commands();
+    println!();
+
+    let mut caller = "alice".to_string();
+    let mut value = 0u128;
+    let mut storage = Vec::new();
+    let mut events: Vec<(String, String)> = Vec::new();
+
+    loop {
+        let prompt = match value {
+            0 => format!("{}> ", caller),
+            _ => format!("{} +{}> ", caller, value),
+        };
+        let line = match editor.readline(&prompt) {
+            Ok(line) => line,
+            Err(ReadlineError::Interrupted | ReadlineError::Eof) => break,
+            Err(e) => {
+                println!("{}", e.to_string().red());
+                break;
+            }
+        };
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let _ = editor.add_history_entry(line);
+
+        let (command, rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+        let rest = rest.trim();
+        let request = match command {
+            ".quit" | ".exit" | ".q" => break,
+            ".help" => {
+                print_interface(&info.id, &contract);
+                print_commands();
+                continue;
+            }
+            ".events" => {
+                if events.is_empty() {
+                    println!("   {}", "No events yet.".dimmed());
+                }
+                for (call, event) in &events {
+                    println!("   {} {} {}", "event".magenta(), event, format!("from {}", call).dimmed());
+                }
+                continue;
+            }
+            ".storage" => {
+                if storage.is_empty() {
+                    println!("   {}", "Deploy the contract first.".dimmed());
+                }
+                for (name, value) in &storage {
+                    println!("   {} {} = {}", "storage".yellow(), name, value);
+                }
+                continue;
+            }
+            ".value" => {
+                match rest.replace('_', "").parse() {
+                    Ok(amount) => value = amount,
+                    Err(_) => println!("   {}", format!("'{}' isn't an amount", rest).red()),
+                }
+                continue;
+            }
+            ".as" => Request::Caller(rest.to_lowercase()),
+            ".advance" => match rest {
+                "" => Request::AdvanceBlocks(1),
+                n => match n.parse() {
+                    Ok(n) => Request::AdvanceBlocks(n),
+                    Err(_) => {
+                        println!("   {}", format!("'{}' isn't a number of blocks", n).red());
+                        continue;
+                    }
+                },
+            },
+            ".timestamp" => match rest.parse() {
+                Ok(t) => Request::SetTimestamp(t),
+                Err(_) => {
+                    println!("   {}", format!("'{}' isn't a timestamp in milliseconds", rest).red());
+                    continue;
+                }
+            },
+            c if c.starts_with('.') => {
+                println!("   {}", format!("Unknown command {}; type .help for the list.", c).red());
+                continue;
+            }
+            _ => match Call::parse(line) {
+                Ok(call) if contract.constructor(&call.name).is_some() => Request::Deploy { call, value },
+                Ok(call) => Request::Call { call, value },
+                Err(e) => {
+                    println!("   {}", e.red());
+                    continue;
+                }
+            },
+        };
+
+        let response = match session.send(&request) {
+            Ok(response) => response,
+            Err(e) => {
+                println!("{}", e.red());
+                break;
+            }
+        };
+        let call = match &request {
+            Request::Deploy { call, .. } | Request::Call { call, .. } => Some(call),
+            _ => None,
+        };
+        if matches!(request, Request::Deploy { .. }) && response.error.is_none() && response.panic.is_none() {
+            storage.clear();
+        }
+        let succeeded = print_response(&contract, call, &response, &mut storage);
+
+        match &request {
+            Request::Caller(account) if succeeded => caller = account.clone(),
+            Request::Deploy { call, .. } | Request::Call { call, .. } => {
+                // A transfer only applies to the call it was attached to
+                value = 0;
+                events.extend(response.events.iter().map(|e| (call.to_string(), e.clone())));
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,140 @@
+//! What's shown while cargo runs: a spinner following crate compilation, or
+//! with `--live`, cargo's own output as it arrives. Nothing is shown with
+//! `--format json`, which keeps stdout for events.
+
+use crate::report;
+use colored::*;
+use indicatif::{ProgressBar, ProgressStyle};
+use inklings::cargo::Stream;
+use serde_json::Value;
+use std::time::{Duration, Instant};
+
+/// Tracks one cargo invocation.
+pub struct BuildProgress {
+    spinner: Option<ProgressBar>,
+    live: bool,
+    label: String,
+    crates: usize,
+    started: Instant,
+}
+
+impl BuildProgress {
+    /// `label` says what's happening, e.g. "Building intro1". Without `live`
+    /// a spinner is shown (when stderr is a terminal); with it, every line
+    /// cargo prints is echoed instead.
+    pub fn start(label: String, live: bool) -> Self {
+        let live = live && !report::is_json();
+        let spinner = (!live && !report::is_json()).then(|| {
+            let spinner = ProgressBar::new_spinner();
+            spinner.set_style(
+                ProgressStyle::with_template("{spinner:.cyan} {msg} {elapsed:.dim}")
+                    .unwrap()
+                    .tick_chars("⠋⠙⠹⠸⠼⠴⠦⠧⠇⠏ "),
+            );
+            spinner.enable_steady_tick(Duration::from_millis(100));
+            spinner.set_message(label.clone());
+            spinner
+        });
+        BuildProgress {
+            spinner,
+            live,
+            label,
+            crates: 0,
+            started: Instant::now(),
+        }
+    }
+
+    /// Feeds one line of cargo's output, run with `--message-format=json`.
+    pub fn line(&mut self, stream: Stream, line: &str) {
+        if self.live {
+            echo(stream, line);
+            return;
+        }
+        let Some(spinner) = &self.spinner else {
+            return;
+        };
+        if stream == Stream::Stderr {
+            return;
+        }
+        match serde_json::from_str::<Value>(line) {
+            Ok(message) if message["reason"] == "compiler-artifact" => {
+                self.crates += 1;
+                let name = package_name(message["package_id"].as_str().unwrap_or_default());
+                let crates = if self.crates == 1 { "crate" } else { "crates" };
+                spinner.set_message(format!(
+                    "{} {}",
+                    self.label,
+                    format!("({} {}, last {})", self.crates, crates, name).dimmed()
+                ));
+            }
+            Ok(_) => {}
+            // Anything that isn't JSON comes from the test binary
+            Err(_) => spinner.set_message(format!("{} {}", self.label, "(running tests)".dimmed())),
+        }
+    }
+
+    /// Removes the spinner and returns how long the step took.
+    pub fn finish(self) -> Duration {
+        if let Some(spinner) = self.spinner {
+            spinner.finish_and_clear();
+        }
+        self.started.elapsed()
+    }
+}
+
+/// The package name in a cargo package id, which looks like
+/// `registry+https://...#syn@2.0.1`, `path+file:///.../intro1#0.1.0`, or in
+/// older cargo versions `syn 2.0.1 (registry+...)`.
+fn package_name(id: &str) -> &str {
+    match id.split_once('#') {
+        Some((_, fragment)) if fragment.contains('@') => fragment.split('@').next().unwrap_or(fragment),
+        Some((source, _)) => source.rsplit('/').next().unwrap_or(source),
+        None => id.split(' ').next().unwrap_or(id),
+    }
+}
+
+/// Prints a line of cargo's output the way a plain cargo run would show it.
+fn echo(stream: Stream, line: &str) {
+    if stream == Stream::Stderr {
+        eprintln!("{}", line);
+        return;
+    }
+    match serde_json::from_str::<Value>(line) {
+        Ok(message) => {
+            if message["reason"] == "compiler-message"
+                && let Some(rendered) = message["message"]["rendered"].as_str()
+            {
+                eprint!("{}", rendered);
+            }
+        }
+        Err(_) => println!("{}", line),
+    }
+}
+
+/// `1.2s`, or `2m 05s` for the long first builds.
+pub fn format_elapsed(elapsed: Duration) -> String {
+    let secs = elapsed.as_secs();
+    if secs >= 60 {
+        format!("{}m {:02}s", secs / 60, secs % 60)
+    } else {
+        format!("{:.1}s", elapsed.as_secs_f64())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_package_names() {
+        assert_eq!(package_name("registry+https://github.com/rust-lang/crates.io-index#syn@2.0.1"), "syn");
+        assert_eq!(package_name("path+file:///root/crate/exercises/01_intro/intro1#0.1.0"), "intro1");
+        assert_eq!(package_name("syn 2.0.1 (registry+https://github.com/rust-lang/crates.io-index)"), "syn");
+    }
+
+    #[test]
+    fn formats_elapsed_time() {
+        assert_eq!(format_elapsed(Duration::from_millis(1250)), "1.2s");
+        assert_eq!(format_elapsed(Duration::from_secs(125)), "2m 05s");
+    }
+}
@@ -0,0 +1,150 @@
+//! Text presentation of the engine's results, the counterpart of `report`
+//! for `--format text`.
+
+use colored::*;
+use inklings::diagnostics::{self, Diagnostic};
+use inklings::libtest::{Outcome, TestReport};
+use inklings::precheck::{self, Pending};
+use inklings::{Curriculum, Progress, prereqs};
+
+/// How many errors are printed before the rest are summarised.
+const MAX_SHOWN: usize = 5;
+
+/// Prints diagnostics with their source lines highlighted, like rustc does.
+pub fn diagnostics(errors: &[Diagnostic], source: &str, file_label: &str) {
+    let lines: Vec<&str> = source.lines().collect();
+    let mut explained = Vec::new();
+
+    for diagnostic in errors.iter().take(MAX_SHOWN) {
+        let header = match diagnostic.code() {
+            Some(code) => format!("error[{}]", code),
+            None => "error".to_string(),
+        };
+        println!("{}: {}", header.red().bold(), diagnostic.message.bold());
+
+        if let Some(span) = diagnostic.primary_span() {
+            let gutter = " ".repeat(span.line_start.to_string().len());
+            println!("{}{} {}:{}:{}", gutter, "-->".blue(), file_label, span.line_start, span.column_start);
+
+            if let Some(text) = lines.get(span.line_start - 1) {
+                let width = if span.line_end == span.line_start {
+                    span.column_end.saturating_sub(span.column_start).max(1)
+                } else {
+                    text.chars().count().saturating_sub(span.column_start - 1).max(1)
+                };
+                println!("{} {}", gutter, "|".blue());
+                println!("{} {} {}", span.line_start.to_string().blue(), "|".blue(), text);
+                println!(
+                    "{} {} {}{} {}",
+                    gutter,
+                    "|".blue(),
+                    " ".repeat(span.column_start - 1),
+                    "^".repeat(width).red().bold(),
+                    span.label.as_deref().unwrap_or("").red()
+                );
+            }
+        }
+
+        // Each explanation is shown once, under the first error it applies to
+        if let Some(text) = diagnostics::explain(diagnostic)
+            && !explained.contains(&text)
+        {
+            explained.push(text);
+            println!("   {} {}", "💡".yellow(), text.yellow());
+        }
+        println!();
+    }
+
+    if errors.len() > MAX_SHOWN {
+        println!("{}", format!("... and {} more error(s)", errors.len() - MAX_SHOWN).dimmed());
+        println!();
+    }
+}
+
+/// Prints one line per test, with the assertion message under each failure.
+pub fn tests(report: &TestReport) {
+    for result in &report.results {
+        match result.outcome {
+            Outcome::Passed => println!("  {} {}", "✓".green(), result.short_name()),
+            Outcome::Ignored => println!("  {} {} {}", "-".dimmed(), result.short_name().dimmed(), "(ignored)".dimmed()),
+            Outcome::Failed => {
+                println!("  {} {}", "✗".red().bold(), result.short_name().red());
+                if let Some(message) = &result.message {
+                    for line in message.lines() {
+                        println!("      {}", line.dimmed());
+                    }
+                }
+            }
+        }
+    }
+
+    println!();
+    println!(
+        "{}",
+        format!("{}/{} tests passing", report.count(Outcome::Passed), report.total()).bold()
+    );
+}
+
+fn format_lines(lines: &[usize]) -> String {
+    lines.iter().map(|l| l.to_string()).collect::<Vec<_>>().join(", ")
+}
+
+/// Explains why an exercise was not handed to cargo.
+pub fn pending(id: &str, pending: &Pending) {
+    if let Some(line) = pending.marker {
+        println!("{}", format!("✋ You haven't marked {} as done yet.", id).yellow().bold());
+        println!(
+            "   Remove the '// {}' line (lib.rs:{}) when you're ready to verify.",
+            precheck::NOT_DONE_MARKER, line
+        );
+    } else {
+        println!(
+            "{}",
+            format!("✋ {} isn't finished yet: {} todo!() site(s) remain.", id, pending.todo_macros.len())
+                .yellow()
+                .bold()
+        );
+        for line in &pending.todo_macros {
+            println!("   lib.rs:{}", line);
+        }
+        println!();
+        println!("{}", "Replace each todo!() with your implementation, then verify again.".dimmed());
+    }
+
+    if !pending.todo_comments.is_empty() {
+        println!(
+            "{}",
+            format!(
+                "   {} TODO comment(s) to work through, on line(s) {}",
+                pending.todo_comments.len(),
+                format_lines(&pending.todo_comments)
+            )
+            .dimmed()
+        );
+    }
+}
+
+/// Prints every exercise with its status and the prerequisites it waits on.
+pub fn prerequisite_graph(config: &Curriculum, progress: &Progress) {
+    for exercise in &config.exercises {
+        let deps = prereqs::requirements(config, &exercise.id);
+        let status = if progress.completed.contains(&exercise.id) {
+            "✓".green().bold()
+        } else if prereqs::is_unlocked(config, progress, &exercise.id) {
+            "○".normal()
+        } else {
+            "🔒".normal()
+        };
+        println!("  {} {}", status, exercise.id.bright_blue());
+
+        for (i, dep) in deps.iter().enumerate() {
+            let branch = if i + 1 == deps.len() { "└─" } else { "├─" };
+            let dep = if progress.completed.contains(dep) {
+                dep.green()
+            } else {
+                dep.dimmed()
+            };
+            println!("      {} {}", branch.dimmed(), dep);
+        }
+    }
+}
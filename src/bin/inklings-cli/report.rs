@@ -0,0 +1,254 @@
+//! `--format json`: output for editors, graders and CI instead of people.
+//!
+//! Everything written to stdout is newline-delimited JSON, one object per
+//! line. Each object has `schema`, the [`SCHEMA_VERSION`] it follows, and
+//! `type`, saying what it describes. Within a version, fields are only ever
+//! added; renaming or removing one bumps it. Nothing else is printed to
+//! stdout, and no spinners are drawn.
+//!
+//! Most commands print a single object:
+//!
+//! - `exercises` (`list`): `exercises`, each with `id`, `name`, `path`, `mode`,
+//!   `category` (or null), `status` (`completed`, `available` or `locked`) and
+//!   `missing` (the incomplete prerequisites); plus `completed` and `total`.
+//! - `progress` (`progress`): `completed` (ids), `total`, `attempts` and
+//!   `hints_revealed` (id to count), `test_scores` (id to `{passed, total}`),
+//!   `active_path` and `last_worked_on` (or null).
+//! - `hint` (`hint`): `exercise`, `shown` (the tier just revealed, from 1),
+//!   `tiers`, `label` and `text`; `shown` is 0 and the rest null when the
+//!   exercise has no hints.
+//! - `doctor` (`doctor`): `issues`, a message for each problem found.
+//! - `paths` (`path list`): `paths`, each with `name`, `completed`, `total`
+//!   and `active`.
+//! - `path` (`path show`, `path select`, `path clear`): `name` (or null),
+//!   `exercises`, each with `id` and `completed`; and `next` (or null).
+//!
+//! `verify`, `watch` and `run` stream events as they happen. Verification
+//! events all carry `exercise`:
+//!
+//! - `verify_started`
+//! - `prerequisites`: `missing` (ids) and `enforced`, whether that stops it.
+//! - `pending`: `todo_macros` and `todo_comments` (line numbers), `marker`
+//!   (the `I AM NOT DONE` line, or null). Nothing is handed to cargo.
+//! - `stage_finished`: `stage` (the cargo subcommand), `success`, `elapsed_ms`
+//!   and `tests` (`{passed, total}`, or null when none ran).
+//! - `diagnostic`: `level`, `code`, `message`, `file`, `line`, `column`,
+//!   `end_line` and `end_column` (all but `level` and `message` may be null;
+//!   `end_column` is exclusive) and `explanation`, a beginner-level reading
+//!   of the error when there is one.
+//! - `output`: `text`, cargo's output when a stage failed outside lib.rs.
+//! - `test`: `name`, `outcome` (`passed`, `failed` or `ignored`), `message`.
+//! - `targeted_hint`: `text`, a hint the manifest ties to the failing test
+//!   or compiler error. (The `hint` command's tiered hints are `hint`.)
+//! - `verify_finished`: `outcome`, `passed`, `unchanged` (passed before with
+//!   the same source) or one of the error kinds below; `next`, the suggested
+//!   exercise after a pass, or null.
+//!
+//! `verify --all`, `--category` and `--path` instead emit
+//! `exercise_verified` (`exercise`, `status`, `tests`, `elapsed_ms`) for
+//! each exercise as it finishes, then `summary` (`passed`, `total`).
+//! `status` is `passed`, `unchanged`, `not_started`, `failed` or `error`.
+//!
+//! `watch` adds `watching` (`exercise`) when it starts on an exercise,
+//! `file_changed` (`exercise`) before re-verifying and `build_cancelled`
+//! (`exercise`) when a change supersedes a running build. Keys aren't read.
+//!
+//! `run` emits `call` for each call: `call`, `returned`, `panic`, `error`,
+//! `events`, `storage` (field to value) and `block` (`{number, timestamp}`
+//! or null). Without calls it prints `interface` (`constructors`, `messages`,
+//! `events`).
+//!
+//! A command that fails ends with an `error` object: `kind` (`not_found`,
+//! `compile_failed`, `tests_failed`, `unfinished`, `config`,
+//! `toolchain_missing`, `io`, `usage` or `interrupted`), `message` and
+//! `exit_code`, which the process exits with.
+
+use crate::error::CliError;
+use clap::ValueEnum;
+use inklings::diagnostics::{self, Diagnostic};
+use inklings::libtest::{Outcome, TestReport};
+use inklings::precheck::Pending;
+use inklings::{Curriculum, Progress, TestScore, prereqs};
+use serde_json::{Map, Value, json};
+use std::cell::RefCell;
+use std::sync::OnceLock;
+
+/// Bumped whenever a field is renamed or removed. 2 renamed the verify
+/// stream's `hint` event to `targeted_hint`.
+pub const SCHEMA_VERSION: u32 = 2;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum Format {
+    /// Coloured output for people
+    #[default]
+    Text,
+    /// Newline-delimited JSON, one object per line
+    Json,
+}
+
+static FORMAT: OnceLock<Format> = OnceLock::new();
+
+/// Set once from the command line, before anything is printed.
+pub fn set_format(format: Format) {
+    let _ = FORMAT.set(format);
+}
+
+pub fn is_json() -> bool {
+    FORMAT.get() == Some(&Format::Json)
+}
+
+type Sink = Box<dyn Fn(Value)>;
+
+thread_local! {
+    /// Where `emit` hands objects instead of printing them, for `inklings serve`.
+    static CAPTURE: RefCell<Option<Sink>> = const { RefCell::new(None) };
+}
+
+/// Prints one object of the given type on its own line.
+pub fn emit(kind: &str, payload: Value) {
+    let object = envelope(kind, payload);
+    CAPTURE.with_borrow(|capture| match capture {
+        Some(sink) => sink(object),
+        None => println!("{}", object),
+    });
+}
+
+/// Sends everything this thread emits to `sink` rather than stdout.
+pub fn capture(sink: impl Fn(Value) + 'static) {
+    CAPTURE.set(Some(Box::new(sink)));
+}
+
+/// `payload` with the schema version and type added, as `emit` prints it.
+pub fn envelope(kind: &str, payload: Value) -> Value {
+    let mut object = Map::new();
+    object.insert("schema".to_string(), json!(SCHEMA_VERSION));
+    object.insert("type".to_string(), json!(kind));
+    if let Value::Object(fields) = payload {
+        object.extend(fields);
+    }
+    Value::Object(object)
+}
+
+pub fn error(error: &CliError) {
+    emit(
+        "error",
+        json!({ "kind": error.kind(), "message": error.to_string(), "exit_code": error.exit_code() }),
+    );
+}
+
+pub fn score(score: Option<TestScore>) -> Value {
+    score.map_or(Value::Null, |s| json!({ "passed": s.passed, "total": s.total }))
+}
+
+/// The `exercises` object for `inklings list`.
+pub fn exercises(config: &Curriculum, progress: &Progress) -> Value {
+    let exercises: Vec<_> = config
+        .exercises
+        .iter()
+        .map(|info| {
+            let missing = prereqs::missing(config, progress, &info.id);
+            let status = if progress.completed.contains(&info.id) {
+                "completed"
+            } else if missing.is_empty() {
+                "available"
+            } else {
+                "locked"
+            };
+            let category = config.categories.iter().find(|(_, c)| c.exercises.contains(&info.id)).map(|(name, _)| name);
+            json!({
+                "id": info.id,
+                "name": info.name,
+                "path": info.path,
+                "mode": info.mode.to_string(),
+                "category": category,
+                "status": status,
+                "missing": missing,
+            })
+        })
+        .collect();
+    json!({ "exercises": exercises, "completed": progress.completed.len(), "total": config.exercises.len() })
+}
+
+/// The `progress` object for `inklings progress`.
+pub fn progress(config: &Curriculum, progress: &Progress) -> Value {
+    // In curriculum order rather than the set's arbitrary one
+    let completed: Vec<_> =
+        config.exercises.iter().filter(|e| progress.completed.contains(&e.id)).map(|e| &e.id).collect();
+    let scores: Map<_, _> = progress.test_scores.iter().map(|(id, s)| (id.clone(), score(Some(*s)))).collect();
+    json!({
+        "completed": completed,
+        "total": config.exercises.len(),
+        "attempts": progress.attempts,
+        "hints_revealed": progress.hints_revealed,
+        "test_scores": scores,
+        "active_path": progress.active_path,
+        "last_worked_on": progress.last_worked_on,
+    })
+}
+
+pub fn pending(id: &str, pending: &Pending) {
+    emit(
+        "pending",
+        json!({
+            "exercise": id,
+            "todo_macros": pending.todo_macros,
+            "todo_comments": pending.todo_comments,
+            "marker": pending.marker,
+        }),
+    );
+}
+
+pub fn diagnostics(id: &str, diagnostics: &[Diagnostic], file: &str) {
+    for diagnostic in diagnostics {
+        let span = diagnostic.primary_span();
+        emit(
+            "diagnostic",
+            json!({
+                "exercise": id,
+                "level": diagnostic.level,
+                "code": diagnostic.code(),
+                "message": diagnostic.message,
+                "file": span.map(|_| file),
+                "line": span.map(|s| s.line_start),
+                "column": span.map(|s| s.column_start),
+                "end_line": span.map(|s| s.line_end),
+                "end_column": span.map(|s| s.column_end),
+                "explanation": diagnostics::explain(diagnostic),
+            }),
+        );
+    }
+}
+
+pub fn tests(id: &str, report: &TestReport) {
+    for result in &report.results {
+        let outcome = match result.outcome {
+            Outcome::Passed => "passed",
+            Outcome::Failed => "failed",
+            Outcome::Ignored => "ignored",
+        };
+        emit(
+            "test",
+            json!({ "exercise": id, "name": result.name, "outcome": outcome, "message": result.message }),
+        );
+    }
+}
+
+pub fn hints(id: &str, hints: &[&str]) {
+    for hint in hints {
+        emit("targeted_hint", json!({ "exercise": id, "text": hint.trim() }));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn objects_carry_schema_and_type() {
+        let object = envelope("verify_started", json!({ "exercise": "01_intro/intro1" }));
+        assert_eq!(object["schema"], SCHEMA_VERSION);
+        assert_eq!(object["type"], "verify_started");
+        assert_eq!(object["exercise"], "01_intro/intro1");
+        assert!(!object.to_string().contains('\n'));
+    }
+}
@@ -0,0 +1,364 @@
+//! `inklings serve --stdio`: JSON-RPC 2.0 for editor extensions, framed with
+//! `Content-Length` headers the way language servers are. The manifest is
+//! read once and re-read when it's edited.
+//!
+//! Requests:
+//!
+//! - `initialize`: `name`, `version` and `schema`, the version of the
+//!   `--format json` schema (src/bin/inklings-cli/report.rs) the results below follow.
+//! - `exercises/list`: the `exercises` object `inklings list` prints.
+//! - `progress/get`: the `progress` object `inklings progress` prints.
+//! - `exercise/hint` (`exercise`): reveals the next hint tier and returns the
+//!   `hint` object `inklings hint` prints.
+//! - `exercise/verify` (`exercise`, optional `fresh` and `force`): verifies
+//!   in the background, sending every event `inklings verify` prints as a
+//!   `verify/event` notification, and answers with the last one,
+//!   `verify_finished`. `$/cancelRequest` stops it.
+//! - `shutdown` cancels running verifications; the `exit` notification, or
+//!   closing stdin, stops the server.
+//!
+//! `exercise` accepts whatever the CLI does: an id, a name or a number.
+//!
+//! Notifications from the server:
+//!
+//! - `exercise/changed` (`exercise`): its files changed, after the same
+//!   debounce as watch mode. Re-verifying is up to the editor.
+//! - `curriculum/changed` (`error`): info/exercises.toml was edited. `error`
+//!   is null, or why the new version couldn't be read; the old one stays.
+//!
+//! A request that fails like a CLI command would has error code -32000,
+//! with `kind` and `exit_code` from the `error` object as its `data`.
+//! If the server can't start (a broken manifest, say), the reason goes to
+//! stderr and it exits with that error's code before reading anything.
+
+use crate::error::CliError;
+use crate::{VerifyOptions, find_exercise, load_progress, report, reveal_hint, verify_finished, verify_resolved, watch};
+use inklings::{CancelToken, Curriculum, Exercise};
+use notify::{Event, RecursiveMode};
+use serde_json::{Value, json};
+use std::collections::HashMap;
+use std::fs;
+use std::io::{self, BufRead, Write};
+use std::path::PathBuf;
+use std::sync::mpsc::{Receiver, channel};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+/// The request failed the way the matching CLI command would.
+const COMMAND_FAILED: i64 = -32000;
+const REQUEST_CANCELLED: i64 = -32800;
+
+struct RpcError {
+    code: i64,
+    message: String,
+    data: Value,
+}
+
+impl RpcError {
+    fn new(code: i64, message: impl Into<String>) -> Self {
+        RpcError { code, message: message.into(), data: Value::Null }
+    }
+}
+
+impl From<CliError> for RpcError {
+    fn from(error: CliError) -> Self {
+        let code = if error == CliError::Interrupted { REQUEST_CANCELLED } else { COMMAND_FAILED };
+        RpcError {
+            code,
+            message: error.to_string(),
+            data: json!({ "kind": error.kind(), "exit_code": error.exit_code() }),
+        }
+    }
+}
+
+/// Reads the body of the next message, or None once the input ends.
+fn read_message(reader: &mut impl BufRead) -> io::Result<Option<Vec<u8>>> {
+    let mut length = None;
+    let mut headers = false;
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line)? == 0 {
+            return Ok(None);
+        }
+        let line = line.trim_end();
+        if line.is_empty() {
+            match length {
+                Some(_) => break,
+                None if headers => return Err(io::Error::new(io::ErrorKind::InvalidData, "missing Content-Length")),
+                // Blank lines between messages
+                None => continue,
+            }
+        }
+        headers = true;
+        if let Some((name, value)) = line.split_once(':')
+            && name.eq_ignore_ascii_case("content-length")
+        {
+            length = value.trim().parse::<usize>().ok();
+        }
+    }
+    let mut body = vec![0; length.unwrap_or_default()];
+    reader.read_exact(&mut body)?;
+    Ok(Some(body))
+}
+
+/// Writes framed messages. Shared by every thread that answers or notifies,
+/// so messages never interleave.
+#[derive(Clone)]
+struct Output(Arc<Mutex<Box<dyn Write + Send>>>);
+
+impl Output {
+    fn new(writer: impl Write + Send + 'static) -> Self {
+        Output(Arc::new(Mutex::new(Box::new(writer))))
+    }
+
+    fn send(&self, message: Value) {
+        let body = message.to_string();
+        let mut writer = self.0.lock().unwrap();
+        let _ = write!(writer, "Content-Length: {}\r\n\r\n{}", body.len(), body);
+        let _ = writer.flush();
+    }
+
+    fn respond(&self, id: Value, result: Result<Value, RpcError>) {
+        self.send(match result {
+            Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+            Err(e) => json!({
+                "jsonrpc": "2.0",
+                "id": id,
+                "error": { "code": e.code, "message": e.message, "data": e.data },
+            }),
+        });
+    }
+
+    fn notify(&self, method: &str, params: Value) {
+        self.send(json!({ "jsonrpc": "2.0", "method": method, "params": params }));
+    }
+}
+
+struct Server {
+    config: Arc<Mutex<Arc<Curriculum>>>,
+    output: Output,
+    /// Cancel tokens of running verifications, by request id.
+    running: Arc<Mutex<HashMap<String, CancelToken>>>,
+    builds: Vec<JoinHandle<()>>,
+}
+
+impl Server {
+    fn config(&self) -> Arc<Curriculum> {
+        self.config.lock().unwrap().clone()
+    }
+
+    /// Handles one incoming message. Returns false once the client asks the server to exit.
+    fn handle(&mut self, message: Value) -> bool {
+        let id = message.get("id").cloned();
+        let Some(method) = message["method"].as_str() else {
+            if let Some(id) = id {
+                self.output.respond(id, Err(RpcError::new(INVALID_REQUEST, "expected a method")));
+            }
+            return true;
+        };
+        let params = message.get("params").cloned().unwrap_or_default();
+
+        match (method, id) {
+            ("exit", _) => return false,
+            ("$/cancelRequest", _) => {
+                if let Some(cancel) = self.running.lock().unwrap().get(&params["id"].to_string()) {
+                    cancel.cancel();
+                }
+            }
+            ("exercise/verify", Some(id)) => self.verify(id, &params),
+            (_, Some(id)) => {
+                let result = self.call(method, &params);
+                self.output.respond(id, result);
+            }
+            // Unknown notifications are ignored, as JSON-RPC asks
+            (_, None) => {}
+        }
+        true
+    }
+
+    /// Answers the requests that finish straight away.
+    fn call(&mut self, method: &str, params: &Value) -> Result<Value, RpcError> {
+        let config = self.config();
+        match method {
+            "initialize" => Ok(json!({
+                "name": "inklings",
+                "version": env!("CARGO_PKG_VERSION"),
+                "schema": report::SCHEMA_VERSION,
+            })),
+            "exercises/list" => Ok(report::envelope("exercises", report::exercises(&config, &load_progress()?))),
+            "progress/get" => Ok(report::envelope("progress", report::progress(&config, &load_progress()?))),
+            "exercise/hint" => Ok(report::envelope("hint", reveal_hint(&exercise_param(&config, params)?)?)),
+            "shutdown" => {
+                self.cancel_all();
+                Ok(Value::Null)
+            }
+            _ => Err(RpcError::new(METHOD_NOT_FOUND, format!("unknown method '{}'", method))),
+        }
+    }
+
+    fn verify(&mut self, id: Value, params: &Value) {
+        let config = self.config();
+        let info = match exercise_param(&config, params) {
+            Ok(info) => info,
+            Err(e) => return self.output.respond(id, Err(e)),
+        };
+        let options = VerifyOptions {
+            force: params["force"].as_bool().unwrap_or(false),
+            fresh: params["fresh"].as_bool().unwrap_or(false),
+            live: false,
+        };
+
+        let cancel = CancelToken::default();
+        let key = id.to_string();
+        self.running.lock().unwrap().insert(key.clone(), cancel.clone());
+        let (output, running) = (self.output.clone(), self.running.clone());
+        self.builds.retain(|build| !build.is_finished());
+        self.builds.push(thread::spawn(move || {
+            let events = output.clone();
+            report::capture(move |event| events.notify("verify/event", event));
+            let result = verify_resolved(&config, &info, options, &cancel);
+            running.lock().unwrap().remove(&key);
+
+            let finished = match verify_finished(&config, &info, &result) {
+                Ok(finished) => report::envelope("verify_finished", finished),
+                Err(e) => return output.respond(id, Err(e.into())),
+            };
+            output.notify("verify/event", finished.clone());
+            output.respond(id, match result {
+                Err(CliError::Interrupted) => Err(CliError::Interrupted.into()),
+                _ => Ok(finished),
+            });
+        }));
+    }
+
+    fn cancel_all(&mut self) {
+        for cancel in self.running.lock().unwrap().values() {
+            cancel.cancel();
+        }
+        for build in self.builds.drain(..) {
+            let _ = build.join();
+        }
+    }
+}
+
+fn exercise_param(config: &Curriculum, params: &Value) -> Result<Exercise, RpcError> {
+    let input = params["exercise"]
+        .as_str()
+        .ok_or_else(|| RpcError::new(INVALID_PARAMS, "expected an 'exercise' string"))?;
+    Ok(find_exercise(config, input)?)
+}
+
+/// Turns file events into `exercise/changed` and `curriculum/changed`
+/// notifications, reloading the manifest into `config` when it changes.
+fn forward_changes(rx: Receiver<notify::Result<Event>>, config: Arc<Mutex<Arc<Curriculum>>>, output: Output) {
+    let manifest = fs::canonicalize("info/exercises.toml").unwrap_or_default();
+    while let Ok(event) = rx.recv() {
+        // Collect the burst a save produces before reporting it once
+        let mut paths: Vec<PathBuf> = Vec::new();
+        let mut next = Some(event);
+        while let Some(event) = next {
+            if let Ok(event) = event
+                && !event.kind.is_access()
+            {
+                paths.extend(event.paths.into_iter().filter(|p| !watch::is_ignored(p)));
+            }
+            next = rx.recv_timeout(watch::DEBOUNCE).ok();
+        }
+
+        if paths.contains(&manifest) {
+            let error = match Curriculum::load() {
+                Ok(reloaded) => {
+                    *config.lock().unwrap() = Arc::new(reloaded);
+                    None
+                }
+                Err(e) => Some(e.to_string()),
+            };
+            output.notify("curriculum/changed", json!({ "error": error }));
+        }
+
+        let current = config.lock().unwrap().clone();
+        for exercise in &current.exercises {
+            let dir = fs::canonicalize(&exercise.path).unwrap_or_else(|_| PathBuf::from(&exercise.path));
+            if paths.iter().any(|p| p.starts_with(&dir)) {
+                output.notify("exercise/changed", json!({ "exercise": exercise.id }));
+            }
+        }
+    }
+}
+
+/// Serves requests from stdin until the client exits or closes it.
+pub fn run(config: Curriculum) -> Result<(), CliError> {
+    let mut server = Server {
+        config: Arc::new(Mutex::new(Arc::new(config))),
+        output: Output::new(io::stdout()),
+        running: Arc::default(),
+        builds: Vec::new(),
+    };
+
+    let (tx, rx) = channel();
+    let _watcher =
+        watch::watch_dirs(tx, &[("exercises", RecursiveMode::Recursive), ("info", RecursiveMode::NonRecursive)])?;
+    let (config, output) = (server.config.clone(), server.output.clone());
+    thread::spawn(move || forward_changes(rx, config, output));
+
+    let mut stdin = io::stdin().lock();
+    loop {
+        let body = match read_message(&mut stdin) {
+            Ok(Some(body)) => body,
+            Ok(None) => break,
+            Err(e) => {
+                server.output.respond(Value::Null, Err(RpcError::new(PARSE_ERROR, e.to_string())));
+                break;
+            }
+        };
+        let message = match serde_json::from_slice(&body) {
+            Ok(message) => message,
+            Err(e) => {
+                server.output.respond(Value::Null, Err(RpcError::new(PARSE_ERROR, e.to_string())));
+                continue;
+            }
+        };
+        if !server.handle(message) {
+            break;
+        }
+    }
+    server.cancel_all();
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_framed_messages() {
+        let input = "Content-Length: 2\r\n\r\n{}\r\nContent-Type: application/json\r\ncontent-length: 4\r\n\r\nnull";
+        let mut reader = io::Cursor::new(input);
+        assert_eq!(read_message(&mut reader).unwrap().unwrap(), b"{}");
+        assert_eq!(read_message(&mut reader).unwrap().unwrap(), b"null");
+        assert!(read_message(&mut reader).unwrap().is_none());
+    }
+
+    #[test]
+    fn answers_requests() {
+        let mut server = Server {
+            config: Arc::new(Mutex::new(Arc::new(Curriculum::load().unwrap()))),
+            output: Output::new(io::sink()),
+            running: Arc::default(),
+            builds: Vec::new(),
+        };
+        let listed = server.call("exercises/list", &Value::Null).ok().unwrap();
+        assert_eq!(listed["type"], "exercises");
+        assert_eq!(listed["total"], 10);
+
+        let missing = server.call("exercise/hint", &json!({})).err().unwrap();
+        assert_eq!(missing.code, INVALID_PARAMS);
+        let unknown = server.call("exercise/hint", &json!({ "exercise": "nope" })).err().unwrap();
+        assert_eq!((unknown.code, &unknown.data["kind"]), (COMMAND_FAILED, &json!("not_found")));
+        assert_eq!(server.call("frobnicate", &Value::Null).err().unwrap().code, METHOD_NOT_FOUND);
+    }
+}
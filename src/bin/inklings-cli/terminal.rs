@@ -0,0 +1,120 @@
+//! Single-keypress input for watch mode, read while builds keep printing.
+
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use crossterm::terminal;
+use std::io::{self, IsTerminal};
+use std::sync::mpsc::{Receiver, channel};
+use std::thread;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Key {
+    Hint,
+    Rerun,
+    Next,
+    List,
+    Solution,
+    Clear,
+    Quit,
+}
+
+/// Shown under the watch banner.
+pub const HELP: &str = "h hint · r rerun · n next · l list · s solution · c clear · q quit";
+
+impl Key {
+    fn from_event(event: KeyEvent) -> Option<Self> {
+        if event.kind != KeyEventKind::Press {
+            return None;
+        }
+        // Raw mode swallows the signal, so Ctrl+C arrives as a key
+        if event.modifiers.contains(KeyModifiers::CONTROL) {
+            return (event.code == KeyCode::Char('c')).then_some(Key::Quit);
+        }
+        let KeyCode::Char(c) = event.code else {
+            return (event.code == KeyCode::Esc).then_some(Key::Quit);
+        };
+        match c.to_ascii_lowercase() {
+            'h' => Some(Key::Hint),
+            'r' => Some(Key::Rerun),
+            'n' => Some(Key::Next),
+            'l' => Some(Key::List),
+            's' => Some(Key::Solution),
+            'c' => Some(Key::Clear),
+            'q' => Some(Key::Quit),
+            _ => None,
+        }
+    }
+}
+
+/// Keeps the terminal in raw mode until dropped.
+pub struct RawMode(());
+
+impl RawMode {
+    /// Returns None when stdin isn't a terminal, e.g. when piped.
+    pub fn enable() -> Option<Self> {
+        if !io::stdin().is_terminal() {
+            return None;
+        }
+        terminal::enable_raw_mode().ok()?;
+        keep_newlines();
+        Some(RawMode(()))
+    }
+}
+
+impl Drop for RawMode {
+    fn drop(&mut self) {
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+/// Raw mode also turns off output processing, after which `\n` no longer
+/// returns to the first column and everything printed would staircase.
+#[cfg(unix)]
+fn keep_newlines() {
+    use rustix::termios::{OptionalActions, OutputModes, tcgetattr, tcsetattr};
+
+    let stdin = io::stdin();
+    if let Ok(mut termios) = tcgetattr(&stdin) {
+        termios.output_modes |= OutputModes::OPOST | OutputModes::ONLCR;
+        let _ = tcsetattr(&stdin, OptionalActions::Now, &termios);
+    }
+}
+
+#[cfg(not(unix))]
+fn keep_newlines() {}
+
+/// Forwards watch-mode keys from a background thread.
+pub fn listen() -> Receiver<Key> {
+    let (tx, rx) = channel();
+    thread::spawn(move || {
+        while let Ok(event) = event::read() {
+            if let Event::Key(key) = event
+                && let Some(key) = Key::from_event(key)
+                && tx.send(key).is_err()
+            {
+                return;
+            }
+        }
+    });
+    rx
+}
+
+/// Clears the screen, leaving the cursor top-left.
+pub fn clear() {
+    use crossterm::{cursor::MoveTo, execute, terminal::Clear, terminal::ClearType};
+    let _ = execute!(io::stdout(), Clear(ClearType::All), MoveTo(0, 0));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn maps_keys_to_actions() {
+        let press = |code, modifiers| Key::from_event(KeyEvent::new(code, modifiers));
+        assert_eq!(press(KeyCode::Char('h'), KeyModifiers::NONE), Some(Key::Hint));
+        assert_eq!(press(KeyCode::Char('Q'), KeyModifiers::SHIFT), Some(Key::Quit));
+        assert_eq!(press(KeyCode::Char('c'), KeyModifiers::NONE), Some(Key::Clear));
+        assert_eq!(press(KeyCode::Char('c'), KeyModifiers::CONTROL), Some(Key::Quit));
+        assert_eq!(press(KeyCode::Char('x'), KeyModifiers::NONE), None);
+    }
+}
@@ -0,0 +1,299 @@
+//! `inklings watch`: re-verifies an exercise as it's edited.
+
+use crate::error::CliError;
+use crate::terminal::{self, Key, RawMode};
+use crate::{VerifyOptions, list_exercises, report, resolve_exercise, show_hint, update_progress, verify_exercise};
+use inklings::{CancelToken, Curriculum, Exercise, Progress, doctor, paths};
+use colored::*;
+use notify::{Config, Event, RecommendedWatcher, RecursiveMode, Watcher};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{Receiver, RecvTimeoutError, Sender, channel};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+/// Sends changes under each of `dirs` to `tx` for as long as the returned watcher lives.
+pub fn watch_dirs(
+    tx: Sender<notify::Result<Event>>,
+    dirs: &[(&str, RecursiveMode)],
+) -> Result<RecommendedWatcher, CliError> {
+    let io_error = |e: notify::Error| CliError::Io(format!("couldn't watch the exercise files: {}", e));
+    let mut watcher = RecommendedWatcher::new(tx, Config::default()).map_err(io_error)?;
+    for (dir, mode) in dirs {
+        watcher.watch(Path::new(dir), *mode).map_err(io_error)?;
+    }
+    Ok(watcher)
+}
+
+/// How long the exercise has to stay untouched before a change is verified.
+/// Editors write a file in several steps, and one save shouldn't mean several builds.
+pub const DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// Swap, backup and lock files editors write next to the file being edited.
+fn is_editor_temp_file(name: &str) -> bool {
+    name.ends_with('~')
+        || name.starts_with(".#")
+        || (name.starts_with('#') && name.ends_with('#'))
+        || name == "4913" // vim checks it can write to the directory with this
+        || name == ".DS_Store"
+        || name.contains("___jb_")
+        || [".swp", ".swo", ".swx", ".tmp", ".bak", ".orig"]
+            .iter()
+            .any(|ext| name.ends_with(ext))
+}
+
+/// Whether a change to `path` can't affect the exercise's build: build
+/// output, version control and editor temp files.
+pub fn is_ignored(path: &Path) -> bool {
+    let in_ignored_dir = path.components().any(|c| matches!(c.as_os_str().to_str(), Some("target" | ".git")));
+    let temp_file = path.file_name().and_then(|n| n.to_str()).is_some_and(is_editor_temp_file);
+    in_ignored_dir || temp_file
+}
+
+/// Whether `event` is an edit in `dir` worth re-verifying for.
+fn is_relevant(event: &Event, dir: &Path) -> bool {
+    !event.kind.is_access() && event.paths.iter().any(|path| path.starts_with(dir) && !is_ignored(path))
+}
+
+/// Waits for the burst of events a save produces to die down.
+fn settle(rx: &Receiver<notify::Result<Event>>) {
+    loop {
+        match rx.recv_timeout(DEBOUNCE) {
+            Ok(_) => continue,
+            Err(RecvTimeoutError::Timeout | RecvTimeoutError::Disconnected) => return,
+        }
+    }
+}
+
+/// A verification running in the background so the watcher can keep listening.
+struct Build {
+    handle: JoinHandle<bool>,
+    cancel: CancelToken,
+}
+
+impl Build {
+    fn start(exercise: &Exercise, options: VerifyOptions) -> Self {
+        let cancel = CancelToken::default();
+        let id = exercise.id.clone();
+        let token = cancel.clone();
+        let handle = thread::spawn(move || verify_exercise(&id, options, &token).is_ok());
+        Build { handle, cancel }
+    }
+
+    /// Kills the build's cargo process and waits for the thread to wind down.
+    fn cancel(self) {
+        self.cancel.cancel();
+        let _ = self.handle.join();
+    }
+}
+
+/// The first exercise of the curriculum the learner hasn't completed.
+fn next_incomplete() -> Option<Exercise> {
+    let config = Curriculum::load().ok()?;
+    let progress = Progress::load().ok()?;
+    paths::next_incomplete(&config, &progress).cloned()
+}
+
+/// The exercise `n` skips to. Locked ones are fair game when nothing else is
+/// left; verifying them explains what they build on.
+fn next_after(current: &Exercise) -> Option<Exercise> {
+    let config = Curriculum::load().ok()?;
+    let progress = Progress::load().ok()?;
+    paths::next_after(&config, &progress, &current.id)
+        .or_else(|| {
+            let order = paths::curriculum_order(&config, &progress);
+            let start = order.iter().position(|e| e.id == current.id).map_or(0, |pos| pos + 1);
+            order[start..].iter().copied().find(|e| !progress.completed.contains(&e.id))
+        })
+        .cloned()
+}
+
+/// Prints the reference solution, but only once the exercise is done.
+fn show_solution(exercise: &Exercise) {
+    if !Progress::load().is_ok_and(|progress| progress.completed.contains(&exercise.id)) {
+        println!("{}", format!("🔒 The solution unlocks once you've completed {}.", exercise.id).yellow());
+        return;
+    }
+    let path = PathBuf::from(doctor::solution_path(&exercise.path)).join("lib.rs");
+    match fs::read_to_string(&path) {
+        Ok(source) => {
+            println!("{}", format!("📖 Reference solution ({}):", path.display()).cyan().bold());
+            println!();
+            println!("{}", source);
+        }
+        Err(e) => println!("{}", format!("Couldn't read {}: {}", path.display(), e).red()),
+    }
+}
+
+fn print_banner(exercise: &Exercise, interactive: bool) {
+    if report::is_json() {
+        report::emit("watching", serde_json::json!({ "exercise": exercise.id }));
+        return;
+    }
+    println!("{}", format!("👀 Watching {}...", exercise.id).cyan());
+    if interactive {
+        println!("{}", terminal::HELP.dimmed());
+    } else {
+        println!("{}", "Press Ctrl+C to exit".dimmed());
+    }
+    println!();
+}
+
+fn canonical_dir(exercise: &Exercise) -> PathBuf {
+    fs::canonicalize(&exercise.path).unwrap_or_else(|_| PathBuf::from(&exercise.path))
+}
+
+/// Re-verifies on every change to the watched exercise. Without an exercise,
+/// starts at the first incomplete one and moves on each time one passes.
+pub fn run(exercise: Option<String>, options: VerifyOptions) -> Result<(), CliError> {
+    let follow = exercise.is_none();
+    let current = match exercise {
+        Some(input) => Some(resolve_exercise(&input)?),
+        None => next_incomplete(),
+    };
+    let Some(mut current) = current else {
+        if !report::is_json() {
+            println!("{}", "🏆 You've completed every exercise!".bright_green().bold());
+        }
+        return Ok(());
+    };
+
+    let (tx, rx) = channel();
+    let _watcher = watch_dirs(tx, &[("exercises", RecursiveMode::Recursive)]).inspect_err(|e| {
+        if !report::is_json() {
+            println!("{}", e.to_string().red());
+        }
+    })?;
+
+    // Keys are only read from a terminal; piped runs behave as before, and
+    // a tool asking for JSON drives the watch by editing files
+    let raw_mode = if report::is_json() { None } else { RawMode::enable() };
+    let keys = raw_mode.as_ref().map(|_| terminal::listen());
+
+    print_banner(&current, keys.is_some());
+    let mut dir = canonical_dir(&current);
+    let mut build = Some(Build::start(&current, options));
+
+    loop {
+        if build.as_ref().is_some_and(|b| b.handle.is_finished()) {
+            let passed = build.take().unwrap().handle.join().unwrap_or(false);
+            if passed && follow {
+                let Some(next) = next_incomplete() else {
+                    return Ok(());
+                };
+                if report::is_json() {
+                    print_banner(&next, false);
+                } else {
+                    println!();
+                    println!("{}", format!("➡️  Moving on to {} - {}", next.id, next.name).cyan().bold());
+                    println!();
+                }
+                current = next;
+                dir = canonical_dir(&current);
+                build = Some(Build::start(&current, options));
+                continue;
+            }
+        }
+
+        let key = keys.as_ref().and_then(|keys| keys.try_recv().ok());
+        match key {
+            // Both report their own failures, and neither should end the watch
+            Some(Key::Hint) => {
+                println!();
+                let _ = show_hint(&current.id);
+            }
+            Some(Key::List) => {
+                println!();
+                let _ = list_exercises();
+            }
+            Some(Key::Solution) => {
+                println!();
+                show_solution(&current);
+            }
+            Some(Key::Clear) => {
+                terminal::clear();
+                print_banner(&current, true);
+            }
+            Some(Key::Rerun) => {
+                if let Some(running) = build.take() {
+                    running.cancel();
+                }
+                println!("\n{}", "Re-verifying...".yellow());
+                build = Some(Build::start(&current, VerifyOptions { fresh: true, ..options }));
+            }
+            Some(Key::Next) => match next_after(&current) {
+                Some(next) => {
+                    if let Some(running) = build.take() {
+                        running.cancel();
+                    }
+                    println!();
+                    println!("{}", format!("⏭  Skipping to {} - {}", next.id, next.name).cyan().bold());
+                    println!();
+                    current = next;
+                    dir = canonical_dir(&current);
+                    build = Some(Build::start(&current, options));
+                }
+                None => println!("\n{}", "There's no exercise left to skip to.".yellow()),
+            },
+            Some(Key::Quit) => {
+                if let Some(running) = build.take() {
+                    running.cancel();
+                }
+                update_progress(|progress| progress.last_worked_on = Some(current.id.clone()))?;
+                println!("\n{}", "👋 Progress saved. See you next time!".cyan());
+                return Ok(());
+            }
+            None => {}
+        }
+
+        let event = match rx.recv_timeout(Duration::from_millis(100)) {
+            Ok(Ok(event)) => event,
+            Ok(Err(_)) | Err(RecvTimeoutError::Timeout) => continue,
+            Err(RecvTimeoutError::Disconnected) => return Ok(()),
+        };
+        // Edits to other exercises don't concern the one being worked on
+        if !is_relevant(&event, &dir) {
+            continue;
+        }
+        settle(&rx);
+
+        if let Some(outdated) = build.take() {
+            let running = !outdated.handle.is_finished();
+            outdated.cancel();
+            if running && report::is_json() {
+                report::emit("build_cancelled", serde_json::json!({ "exercise": current.id }));
+            } else if running {
+                println!("\n{}", "⏹  Cancelled the build of the previous version".dimmed());
+            }
+        }
+        if report::is_json() {
+            report::emit("file_changed", serde_json::json!({ "exercise": current.id }));
+        } else {
+            println!("\n{}", "File changed, re-verifying...".yellow());
+        }
+        build = Some(Build::start(&current, options));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ignores_build_output_and_editor_files() {
+        for path in [
+            "exercises/01_intro/intro1/target/debug/build/foo.d",
+            "exercises/01_intro/intro1/.lib.rs.swp",
+            "exercises/01_intro/intro1/lib.rs~",
+            "exercises/01_intro/intro1/.#lib.rs",
+            "exercises/01_intro/intro1/#lib.rs#",
+            "exercises/01_intro/intro1/4913",
+            "exercises/01_intro/intro1/lib.rs___jb_tmp___",
+        ] {
+            assert!(is_ignored(Path::new(path)), "{path} should be ignored");
+        }
+        for path in ["exercises/01_intro/intro1/lib.rs", "exercises/01_intro/intro1/Cargo.toml"] {
+            assert!(!is_ignored(Path::new(path)), "{path} should be watched");
+        }
+    }
+}
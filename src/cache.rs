@@ -0,0 +1,68 @@
+//! Lets `verify` skip exercises that haven't changed since they last passed.
+
+use crate::{Exercise, Progress};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Every file under `dir` that can affect a build.
+fn source_files(dir: &Path, files: &mut Vec<PathBuf>) -> io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        let name = path.file_name().and_then(|n| n.to_str()).unwrap_or_default();
+        if name == "target" || name.starts_with('.') {
+            continue;
+        }
+        if path.is_dir() {
+            source_files(&path, files)?;
+        } else {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// Hashes the exercise's files together with how it's graded, so changing
+/// its mode in the manifest also counts as a change.
+pub fn source_hash(info: &Exercise) -> io::Result<String> {
+    hash_dir(Path::new(&info.path), &info.mode.to_string())
+}
+
+fn hash_dir(dir: &Path, salt: &str) -> io::Result<String> {
+    let mut files = Vec::new();
+    source_files(dir, &mut files)?;
+    // Directory iteration order isn't stable
+    files.sort();
+
+    let mut hasher = Sha256::new();
+    hasher.update(salt.as_bytes());
+    for file in files {
+        let relative = file.strip_prefix(dir).unwrap_or(&file);
+        hasher.update(relative.to_string_lossy().as_bytes());
+        hasher.update([0]);
+        hasher.update(fs::read(&file)?);
+        hasher.update([0]);
+    }
+    Ok(hasher.finalize().iter().map(|b| format!("{:02x}", b)).collect())
+}
+
+/// Whether the exercise passed before with exactly this source.
+pub fn is_unchanged(progress: &Progress, id: &str, hash: &str) -> bool {
+    progress.completed.contains(id) && progress.verified_sources.get(id).is_some_and(|h| h == hash)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hash_follows_the_sources_and_mode() {
+        let exercise = Path::new("exercises/01_intro/intro1");
+        let hash = hash_dir(exercise, "compile").unwrap();
+        assert_eq!(hash.len(), 64);
+        assert_eq!(hash, hash_dir(exercise, "compile").unwrap());
+        assert_ne!(hash, hash_dir(exercise, "test").unwrap());
+        assert_ne!(hash, hash_dir(Path::new("solutions/01_intro/intro1"), "compile").unwrap());
+    }
+}
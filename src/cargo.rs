@@ -0,0 +1,161 @@
+//! Running cargo so that its output can be followed as it happens and a build
+//! abandoned part-way through.
+
+use std::io::{self, BufRead, BufReader, Read};
+use std::path::Path;
+use std::process::{Child, Command, Output, Stdio};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{RecvTimeoutError, Sender, channel};
+use std::thread;
+use std::time::Duration;
+
+/// How often a running build checks whether it has been cancelled.
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// A `cargo` command building into `target_dir`, usually `Curriculum::target_dir`.
+pub fn command(target_dir: &Path) -> Command {
+    let mut command = Command::new("cargo");
+    command.env("CARGO_TARGET_DIR", target_dir);
+    command
+}
+
+/// Shared flag that tells a running build to stop. Cloning shares the flag.
+#[derive(Clone, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::SeqCst)
+    }
+}
+
+#[derive(Debug)]
+pub enum RunError {
+    Io(io::Error),
+    /// The token was cancelled and the process killed.
+    Cancelled,
+}
+
+/// Which of the process's pipes a line came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stream {
+    Stdout,
+    Stderr,
+}
+
+/// Forwards `pipe` line by line until it closes.
+fn forward<R: Read + Send + 'static>(pipe: Option<R>, stream: Stream, tx: Sender<(Stream, Vec<u8>)>) {
+    let Some(pipe) = pipe else {
+        return;
+    };
+    thread::spawn(move || {
+        let mut reader = BufReader::new(pipe);
+        loop {
+            let mut line = Vec::new();
+            match reader.read_until(b'\n', &mut line) {
+                Ok(0) | Err(_) => return,
+                Ok(_) => {
+                    if tx.send((stream, line)).is_err() {
+                        return;
+                    }
+                }
+            }
+        }
+    });
+}
+
+fn kill(child: &mut Child) {
+    // rustc processes cargo already started finish their current crate and
+    // exit; cargo won't schedule any more
+    let _ = child.kill();
+    let _ = child.wait();
+}
+
+/// Like `Command::output`, but hands each line to `on_line` as it arrives
+/// and kills the process if `cancel` fires first.
+pub fn run(
+    command: &mut Command,
+    cancel: &CancelToken,
+    mut on_line: impl FnMut(Stream, &str),
+) -> Result<Output, RunError> {
+    if cancel.is_cancelled() {
+        return Err(RunError::Cancelled);
+    }
+    let mut child = command
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(RunError::Io)?;
+
+    let (tx, rx) = channel();
+    forward(child.stdout.take(), Stream::Stdout, tx.clone());
+    forward(child.stderr.take(), Stream::Stderr, tx);
+
+    let mut stdout = Vec::new();
+    let mut stderr = Vec::new();
+    // Both pipes close when the process exits
+    loop {
+        if cancel.is_cancelled() {
+            kill(&mut child);
+            return Err(RunError::Cancelled);
+        }
+        match rx.recv_timeout(POLL_INTERVAL) {
+            Ok((stream, line)) => {
+                on_line(stream, String::from_utf8_lossy(&line).trim_end());
+                match stream {
+                    Stream::Stdout => stdout.extend(line),
+                    Stream::Stderr => stderr.extend(line),
+                }
+            }
+            Err(RecvTimeoutError::Timeout) => {}
+            Err(RecvTimeoutError::Disconnected) => break,
+        }
+    }
+
+    let status = child.wait().map_err(RunError::Io)?;
+    Ok(Output { status, stdout, stderr })
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::time::Instant;
+
+    #[test]
+    fn collects_and_streams_output() {
+        let mut lines = Vec::new();
+        let output = run(
+            Command::new("sh").args(["-c", "echo out; echo err >&2; echo more"]),
+            &CancelToken::default(),
+            |stream, line| lines.push((stream, line.to_string())),
+        )
+        .unwrap();
+        assert!(output.status.success());
+        assert_eq!(output.stdout, b"out\nmore\n");
+        assert_eq!(output.stderr, b"err\n");
+
+        let stdout: Vec<_> = lines.iter().filter(|(s, _)| *s == Stream::Stdout).map(|(_, l)| l.as_str()).collect();
+        assert_eq!(stdout, ["out", "more"]);
+    }
+
+    #[test]
+    fn cancelling_kills_the_process() {
+        let cancel = CancelToken::default();
+        let trigger = cancel.clone();
+        thread::spawn(move || {
+            thread::sleep(Duration::from_millis(100));
+            trigger.cancel();
+        });
+
+        let started = Instant::now();
+        let result = run(Command::new("sleep").arg("10"), &cancel, |_, _| {});
+        assert!(matches!(result, Err(RunError::Cancelled)));
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+}
@@ -0,0 +1,427 @@
+//! Reads a contract's interface (constructors, messages, events and storage
+//! fields) straight from its ink! source, the same information `cargo contract`
+//! would put in the metadata, without needing a contract build.
+//!
+//! This reads the source as written rather than expanding it, which is what
+//! a half-finished exercise needs, but it has limits a metadata build
+//! wouldn't:
+//!
+//! - only items written inside the `#[ink::contract]` module's file are seen,
+//!   not ones generated by other macros or pulled in with `mod`/`include!`;
+//! - types are reported as spelled (`Balance`, not what the alias means);
+//! - a signature must end in a `{` body, so messages declared by
+//!   `#[ink::trait_definition]` count only where they're implemented.
+//!
+//! `inklings run` relies on this for argument checking and event decoding,
+//! so those share the limits.
+//!
+//! Comments and string and char literals are blanked out before parsing
+//! (`code_only`), so braces and attributes inside them are ignored. The
+//! same scan lets `precheck` look for markers in comments only.
+
+use regex::Regex;
+use std::fmt;
+use std::ops::Range;
+use std::sync::LazyLock;
+
+static MODULE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"#\[ink::contract[^\]]*\]\s*(?:pub\s+)?mod\s+(\w+)").unwrap());
+static ATTRIBUTE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"#\[ink\((storage|event|constructor|message)([^\]]*)\)\]").unwrap());
+static FUNCTION: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?s)^(?:\s*#\[[^\]]*\])*\s*(?:pub\s+)?fn\s+(\w+)\s*\(([^;{]*?)\)\s*(?:->\s*([^;{]+?))?\s*\{").unwrap()
+});
+static STRUCT: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?s)^(?:\s*#\[[^\]]*\])*\s*pub\s+struct\s+(\w+)").unwrap());
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Param {
+    pub name: String,
+    pub ty: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Callable {
+    pub name: String,
+    pub params: Vec<Param>,
+    pub returns: Option<String>,
+    /// `&mut self` for messages; always true for constructors.
+    pub mutates: bool,
+    pub payable: bool,
+}
+
+impl fmt::Display for Callable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let params: Vec<_> = self.params.iter().map(|p| format!("{}: {}", p.name, p.ty)).collect();
+        write!(f, "{}({})", self.name, params.join(", "))?;
+        if let Some(returns) = &self.returns {
+            write!(f, " -> {}", returns)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Field {
+    pub name: String,
+    pub ty: String,
+}
+
+/// An `#[ink(event)]` struct.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Event {
+    pub name: String,
+    /// In declaration order, which is also the order they're encoded in.
+    pub fields: Vec<Field>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ContractInfo {
+    pub module: String,
+    pub storage: String,
+    pub fields: Vec<Field>,
+    pub constructors: Vec<Callable>,
+    pub messages: Vec<Callable>,
+    pub events: Vec<Event>,
+}
+
+impl ContractInfo {
+    pub fn constructor(&self, name: &str) -> Option<&Callable> {
+        self.constructors.iter().find(|c| c.name == name)
+    }
+
+    pub fn message(&self, name: &str) -> Option<&Callable> {
+        self.messages.iter().find(|m| m.name == name)
+    }
+}
+
+/// What a span of source text that isn't code is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SpanKind {
+    Comment,
+    /// The contents of a string or char literal, without its quotes.
+    Literal,
+}
+
+/// The comments and literal contents in `source`, in order.
+fn spans(source: &str) -> Vec<(Range<usize>, SpanKind)> {
+    let bytes = source.as_bytes();
+    let is_ident = |i: usize| i > 0 && (bytes[i - 1].is_ascii_alphanumeric() || bytes[i - 1] == b'_');
+    let mut spans = Vec::new();
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let rest = &bytes[i..];
+        let start = i;
+        if rest.starts_with(b"//") {
+            i += rest.iter().position(|&b| b == b'\n').unwrap_or(rest.len());
+            spans.push((start..i, SpanKind::Comment));
+        } else if rest.starts_with(b"/*") {
+            // Block comments nest
+            let mut depth = 0;
+            while i < bytes.len() {
+                if bytes[i..].starts_with(b"/*") {
+                    depth += 1;
+                    i += 2;
+                } else if bytes[i..].starts_with(b"*/") {
+                    depth -= 1;
+                    i += 2;
+                    if depth == 0 {
+                        break;
+                    }
+                } else {
+                    i += 1;
+                }
+            }
+            spans.push((start..i.min(bytes.len()), SpanKind::Comment));
+        } else if (rest.starts_with(b"r#") || rest.starts_with(b"r\"") || rest.starts_with(b"br")) && !is_ident(i) {
+            // Raw strings end at a quote followed by as many #s as they started with
+            let r = if rest[0] == b'b' { 1 } else { 0 };
+            let hashes = rest[r + 1..].iter().take_while(|&&b| b == b'#').count();
+            if rest.get(r + 1 + hashes) != Some(&b'"') {
+                i += 1;
+                continue;
+            }
+            let open = i + r + 2 + hashes;
+            let mut close = String::from("\"");
+            close.push_str(&"#".repeat(hashes));
+            let end = source[open..].find(&close).map_or(bytes.len(), |p| open + p);
+            spans.push((open..end, SpanKind::Literal));
+            i = (end + close.len()).min(bytes.len());
+        } else if rest[0] == b'"' {
+            i += 1;
+            while i < bytes.len() && bytes[i] != b'"' {
+                i += if bytes[i] == b'\\' { 2 } else { 1 };
+            }
+            spans.push((start + 1..i.min(bytes.len()), SpanKind::Literal));
+            i += 1;
+        } else if rest[0] == b'\'' {
+            // A char literal, unless it's a lifetime like 'a
+            let end = if rest.get(1) == Some(&b'\\') {
+                rest.get(3..).and_then(|r| r.iter().position(|&b| b == b'\'')).map(|p| i + 3 + p)
+            } else {
+                source[i + 1..]
+                    .chars()
+                    .next()
+                    .map(|c| i + 1 + c.len_utf8())
+                    .filter(|&end| bytes.get(end) == Some(&b'\''))
+            };
+            match end {
+                Some(end) => {
+                    spans.push((start + 1..end, SpanKind::Literal));
+                    i = end + 1;
+                }
+                None => i += 1,
+            }
+        } else {
+            i += 1;
+        }
+    }
+    spans
+}
+
+/// Replaces `range` of `text` with spaces, keeping line breaks so line
+/// numbers still match.
+fn blank(text: &mut [u8], range: Range<usize>) {
+    for byte in &mut text[range] {
+        if *byte != b'\n' {
+            *byte = b' ';
+        }
+    }
+}
+
+/// `source` with comments and the contents of string and char literals
+/// replaced by spaces, so commented-out attributes and braces in literals
+/// aren't mistaken for code. Byte offsets and line breaks are unchanged.
+pub fn code_only(source: &str) -> String {
+    let mut code = source.as_bytes().to_vec();
+    for (range, _) in spans(source) {
+        blank(&mut code, range);
+    }
+    String::from_utf8(code).expect("only whole characters are blanked")
+}
+
+/// The other half of `code_only`: only the text of comments, with
+/// everything else replaced by spaces.
+pub fn comments_only(source: &str) -> String {
+    let mut comments = source.as_bytes().to_vec();
+    let mut code_start = 0;
+    for (range, kind) in spans(source) {
+        if kind == SpanKind::Comment {
+            blank(&mut comments, code_start..range.start);
+            code_start = range.end;
+        }
+    }
+    blank(&mut comments, code_start..source.len());
+    String::from_utf8(comments).expect("only whole characters are blanked")
+}
+
+/// Splits on commas that aren't nested inside `<>`, `()` or `[]`.
+pub fn split_top_level(list: &str) -> Vec<String> {
+    let mut parts = Vec::new();
+    let mut depth = 0i32;
+    let mut in_string = false;
+    let mut current = String::new();
+
+    for c in list.chars() {
+        match c {
+            '"' => in_string = !in_string,
+            '<' | '(' | '[' if !in_string => depth += 1,
+            '>' | ')' | ']' if !in_string => depth -= 1,
+            ',' if depth == 0 && !in_string => {
+                parts.push(current.trim().to_string());
+                current.clear();
+                continue;
+            }
+            _ => {}
+        }
+        current.push(c);
+    }
+    if !current.trim().is_empty() {
+        parts.push(current.trim().to_string());
+    }
+    parts
+}
+
+fn parse_callable(after_attribute: &str, is_constructor: bool, args: &str) -> Option<Callable> {
+    let caps = FUNCTION.captures(after_attribute)?;
+    let mut mutates = is_constructor;
+    let mut params = Vec::new();
+
+    for param in split_top_level(&caps[2]) {
+        let compact: String = param.split_whitespace().collect();
+        if compact == "&self" {
+            continue;
+        }
+        if compact == "&mutself" {
+            mutates = true;
+            continue;
+        }
+        let (name, ty) = param.split_once(':')?;
+        params.push(Param {
+            name: name.trim().trim_start_matches("mut ").to_string(),
+            ty: ty.split_whitespace().collect::<Vec<_>>().join(" "),
+        });
+    }
+
+    Some(Callable {
+        name: caps[1].to_string(),
+        params,
+        returns: caps.get(3).map(|r| r.as_str().split_whitespace().collect::<Vec<_>>().join(" ")),
+        mutates,
+        payable: args.contains("payable"),
+    })
+}
+
+fn parse_fields(body: &str) -> Vec<Field> {
+    split_top_level(body)
+        .into_iter()
+        .filter_map(|field| {
+            // Drop field attributes like #[ink(topic)]
+            let field = field.rsplit(']').next().unwrap_or(&field);
+            let (name, ty) = field.split_once(':')?;
+            let name = name.trim().trim_start_matches("pub ").trim();
+            Some(Field {
+                name: name.to_string(),
+                ty: ty.split_whitespace().collect::<Vec<_>>().join(" "),
+            })
+        })
+        .collect()
+}
+
+/// The text between the braces of the struct starting `rest`.
+fn struct_body(rest: &str) -> &str {
+    let body_start = rest.find('{').map(|i| i + 1).unwrap_or(0);
+    let body_end = rest[body_start..].find('}').map(|i| body_start + i).unwrap_or(body_start);
+    &rest[body_start..body_end]
+}
+
+/// Parses the interface of the `#[ink::contract]` module in `source`.
+pub fn parse(source: &str) -> Option<ContractInfo> {
+    let source = code_only(source);
+    let module = MODULE.captures(&source)?[1].to_string();
+    let mut info = ContractInfo {
+        module,
+        ..ContractInfo::default()
+    };
+
+    for caps in ATTRIBUTE.captures_iter(&source) {
+        let whole = caps.get(0).unwrap();
+        let rest = &source[whole.end()..];
+        match &caps[1] {
+            "storage" => {
+                let Some(name) = STRUCT.captures(rest) else {
+                    continue;
+                };
+                info.storage = name[1].to_string();
+                info.fields = parse_fields(struct_body(rest));
+            }
+            "event" => {
+                if let Some(name) = STRUCT.captures(rest) {
+                    info.events.push(Event { name: name[1].to_string(), fields: parse_fields(struct_body(rest)) });
+                }
+            }
+            kind => {
+                let is_constructor = kind == "constructor";
+                if let Some(callable) = parse_callable(rest, is_constructor, &caps[2]) {
+                    if is_constructor {
+                        info.constructors.push(callable);
+                    } else {
+                        info.messages.push(callable);
+                    }
+                }
+            }
+        }
+    }
+
+    Some(info)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn parses_solution_interfaces() {
+        let source = fs::read_to_string("solutions/04_events/events2/lib.rs").unwrap();
+        let info = parse(&source).unwrap();
+
+        assert_eq!(info.module, "events2");
+        assert_eq!(info.storage, "Events2");
+        assert_eq!(info.fields.iter().map(|f| f.name.as_str()).collect::<Vec<_>>(), ["balances", "total_supply"]);
+        assert_eq!(info.fields[0].ty, "Mapping<H160, Balance>");
+        assert_eq!(info.events.iter().map(|e| e.name.as_str()).collect::<Vec<_>>(), ["Transfer", "Approval"]);
+        let transfer = &info.events[0];
+        assert_eq!(transfer.fields.iter().map(|f| f.name.as_str()).collect::<Vec<_>>(), ["from", "to", "value"]);
+        assert_eq!(transfer.fields[0].ty, "Option<H160>");
+
+        let new = info.constructor("new").unwrap();
+        assert_eq!(new.params, [Param { name: "total_supply".into(), ty: "Balance".into() }]);
+
+        let transfer = info.message("transfer").unwrap();
+        assert!(transfer.mutates);
+        assert_eq!(transfer.to_string(), "transfer(to: H160, value: Balance) -> Result<()>");
+        assert!(!info.message("balance_of").unwrap().mutates);
+    }
+
+    #[test]
+    fn ignores_commented_out_attributes() {
+        let source = fs::read_to_string("exercises/01_intro/intro1/lib.rs").unwrap();
+        let info = parse(&source).unwrap();
+        assert_eq!(info.storage, "");
+        assert_eq!(info.messages.iter().map(|m| m.name.as_str()).collect::<Vec<_>>(), ["flip"]);
+        assert_eq!(info.constructors.len(), 2);
+    }
+
+    /// A contract whose comments and literals are full of look-alike code.
+    const TRICKY: &str = r####"
+        #[ink::contract]
+        mod tricky {
+            /* #[ink(message)]
+               pub fn hidden(&self) { /* nested */ } */
+            #[ink(storage)]
+            pub struct Tricky {
+                open: u8,
+            }
+
+            impl Tricky {
+                #[ink(constructor)]
+                pub fn new() -> Self {
+                    let _ = ('{', '\'', b'{', r#"#[ink(message)] pub fn raw(&self) {"#, "// }");
+                    let _: &'static str = "}";
+                    Self { open: b'{' }
+                }
+
+                #[ink(message)]
+                pub fn get(&self) -> u8 {
+                    self.open
+                }
+            }
+        }
+    "####;
+
+    #[test]
+    fn skips_comments_and_literals() {
+        let info = parse(TRICKY).unwrap();
+        assert_eq!(info.storage, "Tricky");
+        assert_eq!(info.fields, [Field { name: "open".into(), ty: "u8".into() }]);
+        assert_eq!(info.constructors.iter().map(|c| c.name.as_str()).collect::<Vec<_>>(), ["new"]);
+        assert_eq!(info.messages.iter().map(|m| m.name.as_str()).collect::<Vec<_>>(), ["get"]);
+
+        let code = code_only(TRICKY);
+        assert_eq!(code.len(), TRICKY.len());
+        assert_eq!(code.matches('{').count(), code.matches('}').count());
+        assert!(code.contains("&'static str"));
+    }
+
+    #[test]
+    fn finds_trait_messages_where_implemented() {
+        let source = fs::read_to_string("solutions/05_advanced/advanced1/lib.rs").unwrap();
+        let info = parse(&source).unwrap();
+        let get_value = info.message("get_value").unwrap();
+        assert_eq!(get_value.returns.as_deref(), Some("u32"));
+        assert_eq!(info.messages.iter().filter(|m| m.name == "get_value").count(), 1);
+        assert!(info.message("other_value").is_some());
+    }
+}
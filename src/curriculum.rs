@@ -0,0 +1,330 @@
+//! The course: info/exercises.toml and the exercises it lists.
+
+use crate::backend::BackendKind;
+use crate::hints::TargetedHint;
+use crate::prereqs::Policy;
+use crate::resolve::{self, ResolveError};
+use indexmap::IndexMap;
+use serde::Deserialize;
+use std::env;
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::path::PathBuf;
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Exercise {
+    pub id: String,
+    pub name: String,
+    pub path: String,
+    pub mode: Mode,
+    /// Single-tier hint, kept for manifests that predate `hints`.
+    #[serde(default)]
+    pub hint: Option<String>,
+    /// Hint tiers, from a nudge to a near-solution.
+    #[serde(default)]
+    pub hints: Vec<String>,
+    /// Hints printed automatically for specific failing tests or compiler errors.
+    #[serde(default)]
+    pub targeted_hints: Vec<TargetedHint>,
+    /// Exercise ids or category names that must be completed first.
+    #[serde(default)]
+    pub requires: Vec<String>,
+}
+
+impl Exercise {
+    pub fn hint_tiers(&self) -> Vec<&str> {
+        if self.hints.is_empty() {
+            self.hint.iter().map(|h| h.as_str()).collect()
+        } else {
+            self.hints.iter().map(|h| h.as_str()).collect()
+        }
+    }
+}
+
+/// Label for the `index`-th of `count` hint tiers.
+pub fn tier_label(index: usize, count: usize) -> &'static str {
+    match (index, count) {
+        (_, 1) => "hint",
+        (0, _) => "nudge",
+        (i, n) if i + 1 == n => "near-solution",
+        _ => "concept",
+    }
+}
+
+/// How an exercise is graded by `inklings verify`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Mode {
+    /// Passes once the crate builds.
+    Compile,
+    /// Builds, then requires the unit tests to pass.
+    Test,
+    /// Builds, then requires `cargo clippy` to report no warnings.
+    Clippy,
+    /// Requires the contract to build without the `std` feature.
+    NoStd,
+    /// Builds, then requires the `e2e-tests` suite to pass against a node.
+    E2e,
+}
+
+impl fmt::Display for Mode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A single cargo invocation in a verification pipeline.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Step {
+    pub subcommand: &'static str,
+    /// Shown while the step runs, e.g. "Building".
+    pub running: &'static str,
+    /// Arguments placed after `--manifest-path <path>`.
+    pub args: &'static [&'static str],
+    pub passed: &'static str,
+    pub failed: &'static str,
+    /// Whether stdout carries libtest results worth scoring.
+    pub runs_tests: bool,
+}
+
+/// First stage of every pipeline: compile errors come back in seconds,
+/// before anything is code-generated.
+const CHECK_STEP: Step = Step {
+    subcommand: "check",
+    running: "Checking",
+    args: &[],
+    runs_tests: false,
+    passed: "✓ Exercise type-checks!",
+    failed: "✗ Compilation failed!",
+};
+
+const BUILD_STEP: Step = Step {
+    subcommand: "build",
+    running: "Building",
+    args: &[],
+    runs_tests: false,
+    passed: "✓ Exercise compiled successfully!",
+    failed: "✗ Compilation failed!",
+};
+
+impl Mode {
+    /// The stages `verify` runs, stopping at the first that fails. Test modes
+    /// go straight from `check` to `test`, which builds what it runs itself.
+    pub fn steps(self) -> Vec<Step> {
+        match self {
+            Mode::Compile => vec![CHECK_STEP, BUILD_STEP],
+            Mode::Test => vec![
+                CHECK_STEP,
+                Step {
+                    subcommand: "test",
+                    running: "Testing",
+                    args: &[],
+                    runs_tests: true,
+                    passed: "✓ All tests passed!",
+                    failed: "✗ Tests failed!",
+                },
+            ],
+            Mode::Clippy => vec![
+                CHECK_STEP,
+                Step {
+                    subcommand: "clippy",
+                    running: "Linting",
+                    args: &["--", "-D", "warnings"],
+                    runs_tests: false,
+                    passed: "✓ No clippy warnings!",
+                    failed: "✗ Clippy found issues!",
+                },
+            ],
+            Mode::NoStd => vec![
+                Step {
+                    args: &["--no-default-features"],
+                    ..CHECK_STEP
+                },
+                Step {
+                    subcommand: "build",
+                    running: "Building",
+                    args: &["--no-default-features"],
+                    runs_tests: false,
+                    passed: "✓ Contract builds without std!",
+                    failed: "✗ Contract does not build without std!",
+                },
+            ],
+            Mode::E2e => vec![
+                CHECK_STEP,
+                Step {
+                    subcommand: "test",
+                    running: "Testing",
+                    args: &["--features", "e2e-tests"],
+                    runs_tests: true,
+                    passed: "✓ End-to-end tests passed!",
+                    failed: "✗ End-to-end tests failed!",
+                },
+            ],
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Mode::Compile => "compile",
+            Mode::Test => "test",
+            Mode::Clippy => "clippy",
+            Mode::NoStd => "no_std",
+            Mode::E2e => "e2e",
+        }
+    }
+
+    pub fn description(self) -> &'static str {
+        match self {
+            Mode::Compile => "the contract must compile",
+            Mode::Test => "the unit tests must pass",
+            Mode::Clippy => "the contract must be free of clippy warnings",
+            Mode::NoStd => "the contract must build without std",
+            Mode::E2e => "the end-to-end tests must pass (requires a local node)",
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Curriculum {
+    pub exercises: Vec<Exercise>,
+    #[serde(default)]
+    pub categories: IndexMap<String, Category>,
+    #[serde(default)]
+    pub learning_paths: IndexMap<String, Vec<String>>,
+    #[serde(default)]
+    pub metadata: Option<Metadata>,
+}
+
+/// A `[categories]` entry: either a plain list of exercise ids, or a table
+/// with `exercises` and the `requires` shared by all of them.
+#[derive(Debug, Deserialize)]
+#[serde(from = "CategoryDef")]
+pub struct Category {
+    pub exercises: Vec<String>,
+    pub requires: Vec<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum CategoryDef {
+    List(Vec<String>),
+    Table {
+        exercises: Vec<String>,
+        #[serde(default)]
+        requires: Vec<String>,
+    },
+}
+
+impl From<CategoryDef> for Category {
+    fn from(def: CategoryDef) -> Self {
+        match def {
+            CategoryDef::List(exercises) => Category {
+                exercises,
+                requires: Vec::new(),
+            },
+            CategoryDef::Table { exercises, requires } => Category { exercises, requires },
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Metadata {
+    pub version: String,
+    pub total_exercises: usize,
+    #[serde(default)]
+    pub prerequisites: Policy,
+    /// Shared cargo target directory for every exercise, relative to the repo root.
+    #[serde(default)]
+    pub target_dir: Option<String>,
+    /// Which built-in backend compiles and tests exercises.
+    #[serde(default)]
+    pub backend: BackendKind,
+}
+
+impl Curriculum {
+    /// Reads info/exercises.toml from the current directory.
+    pub fn load() -> Result<Curriculum, Box<dyn Error>> {
+        let content = fs::read_to_string("info/exercises.toml")?;
+        let config: Curriculum = toml::from_str(&content)?;
+        Ok(config)
+    }
+
+    /// Finds the exercise meant by `input`: an id, a short name or a number.
+    pub fn find(&self, input: &str) -> Result<&Exercise, ResolveError> {
+        resolve::resolve(self, input)
+    }
+
+    pub fn exercise(&self, id: &str) -> Option<&Exercise> {
+        self.exercises.iter().find(|e| e.id == id)
+    }
+
+    /// Where every exercise builds, so the ink! dependency tree is compiled once
+    /// rather than per exercise. `INKLINGS_TARGET_DIR` or `CARGO_TARGET_DIR` win
+    /// over `[metadata] target_dir`, which defaults to `target`.
+    pub fn target_dir(&self) -> PathBuf {
+        let configured = env::var_os("INKLINGS_TARGET_DIR")
+            .or_else(|| env::var_os("CARGO_TARGET_DIR"))
+            .map(PathBuf::from)
+            .or_else(|| self.metadata.as_ref()?.target_dir.as_ref().map(PathBuf::from))
+            .unwrap_or_else(|| PathBuf::from("target"));
+        // Relative to where inklings runs, not to each exercise's manifest
+        match env::current_dir() {
+            Ok(cwd) if configured.is_relative() => cwd.join(configured),
+            _ => configured,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn manifest_modes_deserialize() {
+        let config = Curriculum::load().expect("info/exercises.toml should parse");
+        let intro1 = config.exercise("01_intro/intro1").unwrap();
+        assert_eq!(intro1.mode, Mode::Compile);
+        let stages: Vec<_> = intro1.mode.steps().iter().map(|s| s.subcommand).collect();
+        assert_eq!(stages, ["check", "build"]);
+        let stages: Vec<_> = Mode::Test.steps().iter().map(|s| s.subcommand).collect();
+        assert_eq!(stages, ["check", "test"]);
+    }
+
+    #[test]
+    fn hint_tiers_fall_back_to_single_hint() {
+        let config = Curriculum::load().unwrap();
+        assert!(config.exercises.iter().all(|e| e.hint_tiers().len() == 3));
+
+        let single: Exercise = toml::from_str(
+            r#"
+            id = "x"
+            name = "x"
+            path = "exercises/x"
+            mode = "test"
+            hint = "only one"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(single.hint_tiers(), vec!["only one"]);
+        assert_eq!(tier_label(0, 1), "hint");
+        assert_eq!(
+            (0..3).map(|i| tier_label(i, 3)).collect::<Vec<_>>(),
+            vec!["nudge", "concept", "near-solution"]
+        );
+    }
+
+    #[test]
+    fn unknown_mode_is_rejected() {
+        let err = toml::from_str::<Exercise>(
+            r#"
+            id = "x"
+            name = "x"
+            path = "exercises/x"
+            mode = "benchmark"
+            hint = ""
+            "#,
+        );
+        assert!(err.is_err());
+    }
+}
@@ -0,0 +1,164 @@
+//! rustc's diagnostics from cargo's JSON output, narrowed to the ones in the
+//! exercise source, with plain-language explanations for the errors ink!'s
+//! macros tend to produce.
+
+use regex::Regex;
+use serde::Deserialize;
+use std::path::Path;
+use std::sync::LazyLock;
+
+/// One line of `cargo --message-format=json` output. Only compiler messages are kept.
+#[derive(Deserialize)]
+struct CargoMessage {
+    reason: String,
+    message: Option<Diagnostic>,
+}
+
+/// A rustc diagnostic, trimmed to the fields the CLI presents.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Diagnostic {
+    pub message: String,
+    pub level: String,
+    pub code: Option<DiagnosticCode>,
+    pub spans: Vec<Span>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct DiagnosticCode {
+    pub code: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Span {
+    pub file_name: String,
+    pub line_start: usize,
+    pub column_start: usize,
+    pub column_end: usize,
+    pub line_end: usize,
+    pub is_primary: bool,
+    pub label: Option<String>,
+}
+
+impl Diagnostic {
+    pub fn primary_span(&self) -> Option<&Span> {
+        self.spans.iter().find(|s| s.is_primary)
+    }
+
+    pub fn code(&self) -> Option<&str> {
+        self.code.as_ref().map(|c| c.code.as_str())
+    }
+}
+
+/// Learner-facing explanations for errors that ink!'s macros produce.
+static EXPLANATIONS: LazyLock<Vec<(Regex, &'static str)>> = LazyLock::new(|| {
+    [
+        (
+            r"missing ink! storage struct",
+            "Every contract needs exactly one struct marked with #[ink(storage)]. \
+             It holds the contract's on-chain state.",
+        ),
+        (
+            r"(missing|at least one) ink! message",
+            "A contract needs at least one public function marked with #[ink(message)] \
+             so users can call it.",
+        ),
+        (
+            r"(missing|at least one) ink! constructor",
+            "A contract needs at least one function marked with #[ink(constructor)] \
+             that returns Self.",
+        ),
+        (
+            r#""packed" layout|: (Encode|Decode|TypeInfo|StorageLayout|Packed|Storable|StorableHint<.*>|AutoStorableHint<.*>)` is not satisfied"#,
+            "Types kept in storage or passed to messages must be SCALE-encodable. \
+             Add #[ink::scale_derive(Encode, Decode, TypeInfo)] to your type, and \
+             derive StorageLayout if it lives in storage.",
+        ),
+        (
+            r"macros cannot expand to struct fields",
+            "todo!() can't stand in for struct fields. Replace it with the fields the TODO comments describe.",
+        ),
+        (
+            r"missing fields? .* in initializer",
+            "Your constructor must give every storage field a value.",
+        ),
+    ]
+    .into_iter()
+    .map(|(pattern, text)| (Regex::new(pattern).expect("explanation pattern is valid"), text))
+    .collect()
+});
+
+/// Parses cargo's JSON output, keeping errors whose primary span is in `source_file`.
+///
+/// cargo reports workspace files relative to the workspace root and
+/// dependencies with absolute paths, so only relative paths are considered.
+///
+/// Non-JSON lines (e.g. test harness output) are ignored. Duplicates, which
+/// ink!'s macros tend to produce, are collapsed.
+pub fn parse(stdout: &str, source_file: &Path) -> Vec<Diagnostic> {
+    let mut diagnostics: Vec<Diagnostic> = Vec::new();
+
+    for line in stdout.lines().filter(|l| l.starts_with('{')) {
+        let Ok(CargoMessage { reason, message: Some(diagnostic) }) = serde_json::from_str(line) else {
+            continue;
+        };
+        if reason != "compiler-message" || diagnostic.level != "error" {
+            continue;
+        }
+        let Some(span) = diagnostic.primary_span() else {
+            continue;
+        };
+        let file = Path::new(&span.file_name);
+        if file.is_absolute() || !file.ends_with(source_file) {
+            continue;
+        }
+
+        let duplicate = diagnostics.iter().any(|d| {
+            d.message == diagnostic.message
+                && d.primary_span().map(|s| s.line_start) == Some(span.line_start)
+        });
+        if !duplicate {
+            diagnostics.push(diagnostic);
+        }
+    }
+
+    diagnostics
+}
+
+pub fn explain(diagnostic: &Diagnostic) -> Option<&'static str> {
+    EXPLANATIONS
+        .iter()
+        .find(|(pattern, _)| pattern.is_match(&diagnostic.message))
+        .map(|(_, text)| *text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OUTPUT: &str = r#"{"reason":"compiler-artifact","package_id":"dep"}
+{"reason":"compiler-message","message":{"message":"missing ink! storage struct","level":"error","code":null,"spans":[{"file_name":"exercises/01_intro/intro1/lib.rs","line_start":20,"line_end":20,"column_start":1,"column_end":4,"is_primary":true,"label":null}]}}
+{"reason":"compiler-message","message":{"message":"missing ink! storage struct","level":"error","code":null,"spans":[{"file_name":"exercises/01_intro/intro1/lib.rs","line_start":20,"line_end":20,"column_start":1,"column_end":4,"is_primary":true,"label":null}]}}
+{"reason":"compiler-message","message":{"message":"unused import","level":"warning","code":null,"spans":[{"file_name":"exercises/01_intro/intro1/lib.rs","line_start":3,"line_end":3,"column_start":1,"column_end":4,"is_primary":true,"label":null}]}}
+{"reason":"compiler-message","message":{"message":"something in a dependency","level":"error","code":{"code":"E0277"},"spans":[{"file_name":"/home/u/.cargo/registry/ink/src/lib.rs","line_start":1,"line_end":1,"column_start":1,"column_end":2,"is_primary":true,"label":null}]}}
+test tests::flip_works ... ok
+"#;
+
+    #[test]
+    fn keeps_only_exercise_errors() {
+        let diagnostics = parse(OUTPUT, Path::new("exercises/01_intro/intro1/lib.rs"));
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].message, "missing ink! storage struct");
+        assert!(explain(&diagnostics[0]).unwrap().contains("#[ink(storage)]"));
+    }
+
+    #[test]
+    fn explains_codec_errors() {
+        let diagnostic = Diagnostic {
+            message: "the trait bound `Task: TypeInfo` is not satisfied".to_string(),
+            level: "error".to_string(),
+            code: None,
+            spans: Vec::new(),
+        };
+        assert!(explain(&diagnostic).unwrap().contains("scale_derive"));
+    }
+}
@@ -0,0 +1,277 @@
+//! Checks info/exercises.toml against the exercise tree: missing or
+//! unlisted exercise directories, missing solutions and hints, references to
+//! ids that don't exist, and prerequisite cycles. `Issue::is_breaking` tells
+//! the problems no command can work around from ones worth only a warning.
+
+use crate::{Curriculum, prereqs};
+use regex::Regex;
+use std::collections::HashSet;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+/// A single inconsistency between info/exercises.toml and the exercise tree.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Issue {
+    MissingPath { id: String, path: String },
+    DuplicateId { id: String },
+    MissingSolution { id: String, expected: String },
+    NoHints { id: String },
+    InvalidTargetedHint { id: String, reason: String },
+    DanglingCategoryRef { category: String, id: String },
+    DanglingPathRef { path: String, id: String },
+    DanglingRequirement { owner: String, requirement: String },
+    RequirementCycle { ids: Vec<String> },
+    TotalMismatch { declared: usize, actual: usize },
+    Unlisted { path: String },
+}
+
+impl fmt::Display for Issue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Issue::MissingPath { id, path } => {
+                write!(f, "exercise '{}' points at '{}', which does not exist", id, path)
+            }
+            Issue::DuplicateId { id } => write!(f, "exercise id '{}' is listed more than once", id),
+            Issue::MissingSolution { id, expected } => {
+                write!(f, "exercise '{}' has no solution at '{}'", id, expected)
+            }
+            Issue::NoHints { id } => write!(f, "exercise '{}' has no hints", id),
+            Issue::InvalidTargetedHint { id, reason } => {
+                write!(f, "exercise '{}' has an invalid targeted hint: {}", id, reason)
+            }
+            Issue::DanglingCategoryRef { category, id } => {
+                write!(f, "category '{}' references unknown exercise '{}'", category, id)
+            }
+            Issue::DanglingPathRef { path, id } => {
+                write!(f, "learning path '{}' references unknown exercise '{}'", path, id)
+            }
+            Issue::DanglingRequirement { owner, requirement } => write!(
+                f,
+                "'{}' requires '{}', which is neither an exercise nor a category",
+                owner, requirement
+            ),
+            Issue::RequirementCycle { ids } => {
+                write!(f, "prerequisites form a cycle: {}", ids.join(" -> "))
+            }
+            Issue::TotalMismatch { declared, actual } => write!(
+                f,
+                "[metadata] total_exercises is {} but {} exercises are listed",
+                declared, actual
+            ),
+            Issue::Unlisted { path } => {
+                write!(f, "'{}' contains an exercise that is not listed in the manifest", path)
+            }
+        }
+    }
+}
+
+impl Issue {
+    /// Whether the manifest is broken: it names something that isn't there,
+    /// or its ids and prerequisites don't add up. Other issues are drift the
+    /// exercises still run with, like a checkout without solutions/.
+    pub fn is_breaking(&self) -> bool {
+        matches!(
+            self,
+            Issue::MissingPath { .. }
+                | Issue::DuplicateId { .. }
+                | Issue::DanglingCategoryRef { .. }
+                | Issue::DanglingPathRef { .. }
+                | Issue::DanglingRequirement { .. }
+                | Issue::RequirementCycle { .. }
+        )
+    }
+}
+
+/// Checks the manifest against the directory tree rooted at `root`.
+pub fn check(config: &Curriculum, root: &Path) -> Vec<Issue> {
+    let mut issues = Vec::new();
+    let mut seen = HashSet::new();
+
+    for exercise in &config.exercises {
+        if !seen.insert(exercise.id.as_str()) {
+            issues.push(Issue::DuplicateId { id: exercise.id.clone() });
+        }
+
+        if exercise.hint_tiers().is_empty() {
+            issues.push(Issue::NoHints { id: exercise.id.clone() });
+        }
+
+        for hint in &exercise.targeted_hints {
+            let reason = if !hint.has_trigger() {
+                Some("it needs a `test`, `code` or `message` trigger".to_string())
+            } else {
+                hint.message
+                    .as_deref()
+                    .and_then(|pattern| Regex::new(pattern).err())
+                    .map(|e| e.to_string())
+            };
+            if let Some(reason) = reason {
+                issues.push(Issue::InvalidTargetedHint {
+                    id: exercise.id.clone(),
+                    reason,
+                });
+            }
+        }
+
+        if !root.join(&exercise.path).join("Cargo.toml").is_file() {
+            issues.push(Issue::MissingPath {
+                id: exercise.id.clone(),
+                path: exercise.path.clone(),
+            });
+            continue;
+        }
+
+        let solution = solution_path(&exercise.path);
+        if !root.join(&solution).join("Cargo.toml").is_file() {
+            issues.push(Issue::MissingSolution {
+                id: exercise.id.clone(),
+                expected: solution,
+            });
+        }
+    }
+
+    for (category, entry) in &config.categories {
+        for id in &entry.exercises {
+            if !seen.contains(id.as_str()) {
+                issues.push(Issue::DanglingCategoryRef {
+                    category: category.clone(),
+                    id: id.clone(),
+                });
+            }
+        }
+    }
+    for (path, ids) in &config.learning_paths {
+        for id in ids {
+            if !seen.contains(id.as_str()) {
+                issues.push(Issue::DanglingPathRef {
+                    path: path.clone(),
+                    id: id.clone(),
+                });
+            }
+        }
+    }
+
+    for (owner, requirement) in prereqs::dangling(config) {
+        issues.push(Issue::DanglingRequirement { owner, requirement });
+    }
+    if let Some(ids) = prereqs::find_cycle(config) {
+        issues.push(Issue::RequirementCycle { ids });
+    }
+
+    if let Some(metadata) = &config.metadata
+        && metadata.total_exercises != config.exercises.len()
+    {
+        issues.push(Issue::TotalMismatch {
+            declared: metadata.total_exercises,
+            actual: config.exercises.len(),
+        });
+    }
+
+    let listed: HashSet<&str> = config.exercises.iter().map(|e| e.path.as_str()).collect();
+    for path in exercise_dirs(root) {
+        if !listed.contains(path.as_str()) {
+            issues.push(Issue::Unlisted { path });
+        }
+    }
+
+    issues
+}
+
+/// `exercises/01_intro/intro1` -> `solutions/01_intro/intro1`
+pub fn solution_path(exercise_path: &str) -> String {
+    match exercise_path.strip_prefix("exercises/") {
+        Some(rest) => format!("solutions/{}", rest),
+        None => format!("solutions/{}", exercise_path),
+    }
+}
+
+/// Every `exercises/<category>/<exercise>` directory holding a Cargo.toml.
+fn exercise_dirs(root: &Path) -> Vec<String> {
+    let mut dirs = Vec::new();
+    let Ok(categories) = fs::read_dir(root.join("exercises")) else {
+        return dirs;
+    };
+
+    for category in categories.filter_map(|e| e.ok()) {
+        let Ok(exercises) = fs::read_dir(category.path()) else {
+            continue;
+        };
+        for exercise in exercises.filter_map(|e| e.ok()) {
+            if exercise.path().join("Cargo.toml").is_file() {
+                dirs.push(format!(
+                    "exercises/{}/{}",
+                    category.file_name().to_string_lossy(),
+                    exercise.file_name().to_string_lossy()
+                ));
+            }
+        }
+    }
+
+    dirs.sort();
+    dirs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shipped_manifest_is_consistent() {
+        let config = Curriculum::load().unwrap();
+        assert_eq!(check(&config, Path::new(".")), vec![]);
+    }
+
+    #[test]
+    fn reports_drift() {
+        let config: Curriculum = toml::from_str(
+            r#"
+            [[exercises]]
+            id = "01_intro/intro1"
+            name = "One"
+            path = "exercises/01_intro/intro1"
+            mode = "compile"
+            hint = ""
+
+            [[exercises]]
+            id = "01_intro/intro1"
+            name = "Gone"
+            path = "exercises/99_missing/missing1"
+            mode = "test"
+            hint = ""
+
+            [metadata]
+            version = "0.1.0"
+            total_exercises = 10
+
+            [categories]
+            basics = ["02_basics/basic1"]
+
+            [learning_paths]
+            beginner = ["01_intro/intro1", "02_basics/basic2"]
+            "#,
+        )
+        .unwrap();
+
+        let issues = check(&config, Path::new("."));
+        assert!(issues.contains(&Issue::DuplicateId { id: "01_intro/intro1".into() }));
+        assert!(issues.contains(&Issue::MissingPath {
+            id: "01_intro/intro1".into(),
+            path: "exercises/99_missing/missing1".into(),
+        }));
+        assert!(issues.contains(&Issue::DanglingCategoryRef {
+            category: "basics".into(),
+            id: "02_basics/basic1".into(),
+        }));
+        assert!(issues.contains(&Issue::DanglingPathRef {
+            path: "beginner".into(),
+            id: "02_basics/basic2".into(),
+        }));
+        assert!(issues.contains(&Issue::TotalMismatch { declared: 10, actual: 2 }));
+        assert!(issues.contains(&Issue::Unlisted { path: "exercises/04_events/events2".into() }));
+
+        let warnings: Vec<_> = issues.iter().filter(|issue| !issue.is_breaking()).collect();
+        assert!(warnings.iter().all(|issue| matches!(issue, Issue::TotalMismatch { .. } | Issue::Unlisted { .. })));
+        assert!(issues.iter().any(Issue::is_breaking));
+    }
+}
@@ -0,0 +1,133 @@
+//! Targeted hints: ones the manifest attaches to a failing test or a
+//! compiler error, shown automatically when that failure happens.
+
+use crate::Exercise;
+use crate::diagnostics::Diagnostic;
+use crate::libtest::{Outcome, TestReport};
+use regex::Regex;
+use serde::Deserialize;
+
+/// A hint shown automatically when a specific failure happens.
+///
+/// ```toml
+/// [[exercises.targeted_hints]]
+/// test = "complete_task_not_found"
+/// hint = "..."
+/// ```
+///
+/// Exactly one trigger is expected: `test` (a test's name, matched against the
+/// short or full name), `code` (a rustc error code like `E0063`) or `message`
+/// (a regex matched against the compiler error message).
+#[derive(Debug, Clone, Deserialize)]
+pub struct TargetedHint {
+    pub test: Option<String>,
+    pub code: Option<String>,
+    pub message: Option<String>,
+    pub hint: String,
+}
+
+impl TargetedHint {
+    pub fn has_trigger(&self) -> bool {
+        self.test.is_some() || self.code.is_some() || self.message.is_some()
+    }
+
+    fn matches_diagnostic(&self, diagnostic: &Diagnostic) -> bool {
+        if let Some(code) = &self.code
+            && diagnostic.code() == Some(code.as_str())
+        {
+            return true;
+        }
+        // Invalid patterns are reported by `inklings doctor`; here they just never match
+        self.message
+            .as_deref()
+            .and_then(|pattern| Regex::new(pattern).ok())
+            .is_some_and(|re| re.is_match(&diagnostic.message))
+    }
+}
+
+/// Hints for the tests that failed, in manifest order, without repeats.
+pub fn for_tests<'a>(info: &'a Exercise, report: &TestReport) -> Vec<&'a str> {
+    let failed: Vec<_> = report.results.iter().filter(|r| r.outcome == Outcome::Failed).collect();
+
+    info.targeted_hints
+        .iter()
+        .filter(|hint| {
+            hint.test.as_deref().is_some_and(|test| {
+                failed.iter().any(|r| r.short_name() == test || r.name == test)
+            })
+        })
+        .map(|hint| hint.hint.as_str())
+        .collect()
+}
+
+/// Hints for the compiler errors that were reported.
+pub fn for_diagnostics<'a>(info: &'a Exercise, diagnostics: &[Diagnostic]) -> Vec<&'a str> {
+    info.targeted_hints
+        .iter()
+        .filter(|hint| diagnostics.iter().any(|d| hint.matches_diagnostic(d)))
+        .map(|hint| hint.hint.as_str())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::libtest;
+
+    fn exercise() -> Exercise {
+        toml::from_str(
+            r#"
+            id = "03_storage/storage2"
+            name = "Storage Vectors"
+            path = "exercises/03_storage/storage2"
+            mode = "test"
+            hint = ""
+
+            [[targeted_hints]]
+            test = "complete_task_not_found"
+            hint = "not found"
+
+            [[targeted_hints]]
+            code = "E0063"
+            hint = "missing field"
+
+            [[targeted_hints]]
+            message = "missing ink! storage"
+            hint = "storage"
+            "#,
+        )
+        .unwrap()
+    }
+
+    fn diagnostic(message: &str, code: Option<&str>) -> Diagnostic {
+        serde_json::from_value(serde_json::json!({
+            "message": message,
+            "level": "error",
+            "code": code.map(|c| serde_json::json!({ "code": c })),
+            "spans": [],
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn matches_failed_tests() {
+        let info = exercise();
+        let report = libtest::parse(
+            "test storage2::tests::complete_task_not_found ... FAILED\ntest storage2::tests::new_works ... ok\n",
+        );
+        assert_eq!(for_tests(&info, &report), vec!["not found"]);
+
+        let passing = libtest::parse("test storage2::tests::complete_task_not_found ... ok\n");
+        assert!(for_tests(&info, &passing).is_empty());
+    }
+
+    #[test]
+    fn matches_codes_and_messages() {
+        let info = exercise();
+        let diagnostics = [
+            diagnostic("missing field `owner` in initializer of `Storage2`", Some("E0063")),
+            diagnostic("missing ink! storage struct", None),
+        ];
+        assert_eq!(for_diagnostics(&info, &diagnostics), vec!["missing field", "storage"]);
+    }
+}
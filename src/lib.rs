@@ -0,0 +1,33 @@
+//! The engine behind the `inklings` CLI: the exercise manifest, the learner's
+//! progress, and verification of an exercise against its mode's stages.
+//!
+//! Nothing here prints. `Verifier::verify` streams `verify::Event`s while it
+//! runs and returns a `Verification` to present however the embedding tool
+//! likes (a terminal, an editor, a grader) and to record with
+//! `Progress::record`. Compiling, testing and building go through a
+//! `Backend`, cargo unless the manifest says otherwise. Paths in the
+//! manifest are relative, so the engine expects to run from the repository
+//! root.
+
+pub mod backend;
+pub mod cache;
+pub mod cargo;
+pub mod contract;
+pub mod curriculum;
+pub mod diagnostics;
+pub mod doctor;
+pub mod hints;
+pub mod libtest;
+pub mod paths;
+pub mod precheck;
+pub mod prereqs;
+pub mod progress;
+pub mod resolve;
+pub mod sandbox;
+pub mod verify;
+
+pub use backend::Backend;
+pub use cargo::CancelToken;
+pub use curriculum::{Curriculum, Exercise, Mode, Step};
+pub use progress::{Progress, TestScore};
+pub use verify::{Verification, Verifier, VerifyOutcome};
@@ -0,0 +1,160 @@
+//! Per-test results read from libtest's human-readable output, for partial
+//! credit and for hints tied to specific tests.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    Passed,
+    Failed,
+    Ignored,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct TestResult {
+    /// Full path as libtest reports it, e.g. `storage2::tests::new_works`.
+    pub name: String,
+    pub outcome: Outcome,
+    /// The panic message of a failed test, without the backtrace.
+    pub message: Option<String>,
+}
+
+impl TestResult {
+    /// `storage2::tests::new_works` -> `new_works`
+    pub fn short_name(&self) -> &str {
+        self.name.rsplit("::").next().unwrap_or(&self.name)
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct TestReport {
+    pub results: Vec<TestResult>,
+}
+
+impl TestReport {
+    pub fn count(&self, outcome: Outcome) -> usize {
+        self.results.iter().filter(|r| r.outcome == outcome).count()
+    }
+
+    /// Tests that count towards the score, i.e. everything not ignored.
+    pub fn total(&self) -> usize {
+        self.results.len() - self.count(Outcome::Ignored)
+    }
+}
+
+/// Parses libtest's human-readable output. Other lines (cargo JSON messages,
+/// doc-test sections, ...) are skipped.
+pub fn parse(stdout: &str) -> TestReport {
+    let mut report = TestReport::default();
+
+    for line in stdout.lines() {
+        let Some(rest) = line.strip_prefix("test ") else {
+            continue;
+        };
+        let Some((name, status)) = rest.rsplit_once(" ... ") else {
+            continue;
+        };
+        let outcome = match status.trim() {
+            "ok" => Outcome::Passed,
+            "FAILED" => Outcome::Failed,
+            s if s.starts_with("ignored") => Outcome::Ignored,
+            _ => continue,
+        };
+        report.results.push(TestResult {
+            name: name.to_string(),
+            outcome,
+            message: None,
+        });
+    }
+
+    let messages = failure_messages(stdout);
+    for result in &mut report.results {
+        if let Some((_, message)) = messages.iter().find(|(name, _)| *name == result.name) {
+            result.message = Some(message.clone());
+        }
+    }
+
+    report
+}
+
+/// Collects the panic message from each `---- <name> stdout ----` section.
+fn failure_messages(stdout: &str) -> Vec<(&str, String)> {
+    let mut messages = Vec::new();
+    let mut current: Option<(&str, Vec<&str>, bool)> = None;
+
+    for line in stdout.lines() {
+        if let Some(name) = line.strip_prefix("---- ").and_then(|l| l.strip_suffix(" stdout ----")) {
+            if let Some((name, lines, _)) = current.take() {
+                messages.push((name, lines.join("\n")));
+            }
+            current = Some((name, Vec::new(), false));
+            continue;
+        }
+
+        let Some((_, lines, in_panic)) = current.as_mut() else {
+            continue;
+        };
+        if line == "failures:" {
+            break;
+        }
+        if line.starts_with("thread '") && line.contains("panicked at") {
+            *in_panic = true;
+        } else if *in_panic {
+            if line.is_empty() || line.starts_with("stack backtrace:") || line.starts_with("note: ") {
+                *in_panic = false;
+            } else {
+                lines.push(line);
+            }
+        }
+    }
+
+    if let Some((name, lines, _)) = current {
+        messages.push((name, lines.join("\n")));
+    }
+    messages
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OUTPUT: &str = "\
+{\"reason\":\"build-finished\",\"success\":true}
+
+running 3 tests
+test storage2::tests::new_works ... ok
+test storage2::tests::complete_task_not_found ... FAILED
+test storage2::tests::slow ... ignored, needs a node
+
+failures:
+
+---- storage2::tests::complete_task_not_found stdout ----
+
+thread 'storage2::tests::complete_task_not_found' (22509) panicked at lib.rs:183:13:
+assertion `left == right` failed
+  left: Err(TaskNotFound)
+ right: Err(NoTasks)
+stack backtrace:
+   0: __rustc::rust_begin_unwind
+note: Some details are omitted, run with `RUST_BACKTRACE=full` for a verbose backtrace.
+
+
+failures:
+    storage2::tests::complete_task_not_found
+
+test result: FAILED. 1 passed; 1 failed; 1 ignored; 0 measured; 0 filtered out; finished in 0.07s
+";
+
+    #[test]
+    fn parses_results_and_messages() {
+        let report = parse(OUTPUT);
+        assert_eq!(report.results.len(), 3);
+        assert_eq!(report.count(Outcome::Passed), 1);
+        assert_eq!(report.total(), 2);
+
+        let failed: Vec<_> = report.results.iter().filter(|r| r.outcome == Outcome::Failed).collect();
+        assert_eq!(failed[0].short_name(), "complete_task_not_found");
+        assert_eq!(
+            failed[0].message.as_deref(),
+            Some("assertion `left == right` failed\n  left: Err(TaskNotFound)\n right: Err(NoTasks)")
+        );
+    }
+}
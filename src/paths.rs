@@ -0,0 +1,95 @@
+//! Where the learner goes next: curriculum order along the active learning
+//! path (or the manifest), and the next incomplete exercise in it.
+
+use crate::{Curriculum, Exercise, Progress, prereqs};
+
+/// The order learners should work through exercises in: the active learning
+/// path if one is selected, otherwise the manifest order.
+pub fn curriculum_order<'a>(config: &'a Curriculum, progress: &Progress) -> Vec<&'a Exercise> {
+    match active_path(config, progress) {
+        Some((_, ids)) => ids
+            .iter()
+            .filter_map(|id| config.exercises.iter().find(|e| &e.id == id))
+            .collect(),
+        None => config.exercises.iter().collect(),
+    }
+}
+
+/// The first exercise in curriculum order that hasn't been completed, preferring
+/// ones whose prerequisites are done.
+pub fn next_incomplete<'a>(config: &'a Curriculum, progress: &Progress) -> Option<&'a Exercise> {
+    let pending: Vec<_> = curriculum_order(config, progress)
+        .into_iter()
+        .filter(|e| !progress.completed.contains(&e.id))
+        .collect();
+    pending
+        .iter()
+        .find(|e| prereqs::is_unlocked(config, progress, &e.id))
+        .or(pending.first())
+        .copied()
+}
+
+/// The next unlocked, incomplete exercise after `current_id` in curriculum
+/// order, wrapping around to anything skipped earlier.
+pub fn next_after<'a>(config: &'a Curriculum, progress: &Progress, current_id: &str) -> Option<&'a Exercise> {
+    let order = curriculum_order(config, progress);
+    let start = order.iter().position(|e| e.id == current_id).map_or(0, |pos| pos + 1);
+    order[start..]
+        .iter()
+        .chain(&order[..start])
+        .find(|e| e.id != current_id && !progress.completed.contains(&e.id) && prereqs::is_unlocked(config, progress, &e.id))
+        .copied()
+}
+
+/// The selected learning path, if it still exists in the manifest.
+pub fn active_path<'a>(config: &'a Curriculum, progress: &Progress) -> Option<(&'a str, &'a [String])> {
+    let name = progress.active_path.as_deref()?;
+    config
+        .learning_paths
+        .get_key_value(name)
+        .map(|(name, ids)| (name.as_str(), ids.as_slice()))
+}
+
+/// How many of `ids` have been completed.
+pub fn completed_in(ids: &[String], progress: &Progress) -> usize {
+    ids.iter().filter(|id| progress.completed.contains(*id)).count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn active_path_drives_order() {
+        let config = Curriculum::load().unwrap();
+        let mut progress = Progress::default();
+
+        let order = curriculum_order(&config, &progress);
+        assert_eq!(order.len(), config.exercises.len());
+
+        progress.active_path = Some("intermediate".to_string());
+        let order: Vec<_> = curriculum_order(&config, &progress).iter().map(|e| e.id.as_str()).collect();
+        assert_eq!(order.first(), Some(&"03_storage/storage1"));
+        assert_eq!(order.len(), 4);
+
+        progress.active_path = Some("removed".to_string());
+        assert_eq!(curriculum_order(&config, &progress).len(), config.exercises.len());
+    }
+
+    #[test]
+    fn next_incomplete_follows_the_curriculum() {
+        let config = Curriculum::load().unwrap();
+        let mut progress = Progress::default();
+        assert_eq!(next_incomplete(&config, &progress).unwrap().id, "01_intro/intro1");
+
+        progress.completed.insert("01_intro/intro1".to_string());
+        assert_eq!(next_incomplete(&config, &progress).unwrap().id, "01_intro/intro2");
+
+        // A selected path narrows the curriculum to its own exercises
+        progress.active_path = Some("intermediate".to_string());
+        assert_eq!(next_incomplete(&config, &progress).unwrap().id, "03_storage/storage1");
+
+        progress.completed.extend(config.exercises.iter().map(|e| e.id.clone()));
+        assert!(next_incomplete(&config, &progress).is_none());
+    }
+}
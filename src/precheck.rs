@@ -0,0 +1,91 @@
+//! Spots exercises that can't pass yet (a `todo!()` or the `I AM NOT DONE`
+//! marker) from the source alone, so verification needn't run cargo.
+
+use crate::contract;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// Explicit opt-in marker: while this line is in an exercise, it is never verified.
+pub const NOT_DONE_MARKER: &str = "I AM NOT DONE";
+
+/// What's left to do in an exercise source file, by 1-based line number.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Pending {
+    pub todo_macros: Vec<usize>,
+    pub todo_comments: Vec<usize>,
+    pub marker: Option<usize>,
+}
+
+impl Pending {
+    /// True if the exercise certainly can't pass yet, so cargo needn't run.
+    pub fn blocks_verification(&self) -> bool {
+        self.marker.is_some() || !self.todo_macros.is_empty()
+    }
+}
+
+pub fn scan_source(source: &str) -> Pending {
+    let mut pending = Pending::default();
+    let (code, comments) = (contract::code_only(source), contract::comments_only(source));
+
+    for (index, (code, comment)) in code.lines().zip(comments.lines()).enumerate() {
+        let number = index + 1;
+
+        if code.contains("todo!(") {
+            pending.todo_macros.push(number);
+        }
+        if comment.contains(NOT_DONE_MARKER) {
+            pending.marker.get_or_insert(number);
+        } else if comment.contains("TODO") {
+            pending.todo_comments.push(number);
+        }
+    }
+
+    pending
+}
+
+/// Scans the exercise's `lib.rs`.
+pub fn scan(exercise_dir: &Path) -> io::Result<Pending> {
+    let source = fs::read_to_string(exercise_dir.join("lib.rs"))?;
+    Ok(scan_source(&source))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_todo_sites() {
+        let pending = scan_source(
+            "fn a() {\n    // TODO: implement\n    todo!()\n}\n// Hint: replace todo!() here\nfn b() {}\n",
+        );
+        assert_eq!(pending.todo_macros, vec![3]);
+        assert_eq!(pending.todo_comments, vec![2]);
+        assert!(pending.blocks_verification());
+    }
+
+    #[test]
+    fn ignores_markers_inside_literals() {
+        let pending = scan_source(
+            "let url = \"https://example.com\"; todo!()\nlet s = \"// TODO todo!()\";\n/* TODO: block */\n",
+        );
+        assert_eq!(pending.todo_macros, vec![1]);
+        assert_eq!(pending.todo_comments, vec![3]);
+    }
+
+    #[test]
+    fn marker_is_opt_in() {
+        assert!(!scan_source("// TODO: leftover note\nfn a() {}\n").blocks_verification());
+
+        let pending = scan_source("// I AM NOT DONE\nfn a() {}\n");
+        assert_eq!(pending.marker, Some(1));
+        assert!(pending.blocks_verification());
+    }
+
+    #[test]
+    fn untouched_exercises_are_blocked() {
+        let pending = scan(Path::new("exercises/03_storage/storage2")).unwrap();
+        assert!(pending.blocks_verification());
+        assert!(!scan(Path::new("solutions/03_storage/storage2")).unwrap().blocks_verification());
+    }
+}
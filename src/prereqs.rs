@@ -0,0 +1,209 @@
+//! Exercise prerequisites: what each exercise requires, directly or through
+//! its categories, which of those are still incomplete, and the dangling
+//! references and cycles `doctor` reports.
+
+use crate::{Curriculum, Progress};
+use serde::Deserialize;
+use std::collections::HashSet;
+
+/// What `inklings verify` does when an exercise's prerequisites are incomplete.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Policy {
+    /// Print a warning and verify anyway.
+    #[default]
+    Warn,
+    /// Refuse to verify until the prerequisites are completed.
+    Enforce,
+}
+
+/// The exercise ids `id` depends on directly: its own `requires` plus the
+/// `requires` of every category containing it, with category names expanded
+/// to their exercises.
+pub fn requirements(config: &Curriculum, id: &str) -> Vec<String> {
+    let mut raw: Vec<&str> = Vec::new();
+    if let Some(info) = config.exercises.iter().find(|e| e.id == id) {
+        raw.extend(info.requires.iter().map(String::as_str));
+    }
+    for category in config.categories.values() {
+        if category.exercises.iter().any(|e| e == id) {
+            raw.extend(category.requires.iter().map(String::as_str));
+        }
+    }
+
+    let mut ids = Vec::new();
+    for requirement in raw {
+        let expanded = match config.categories.get(requirement) {
+            Some(category) => category.exercises.clone(),
+            None => vec![requirement.to_string()],
+        };
+        for dep in expanded {
+            if dep != id && !ids.contains(&dep) {
+                ids.push(dep);
+            }
+        }
+    }
+    ids
+}
+
+/// Direct prerequisites of `id` that are not completed yet.
+pub fn missing(config: &Curriculum, progress: &Progress, id: &str) -> Vec<String> {
+    requirements(config, id)
+        .into_iter()
+        .filter(|dep| !progress.completed.contains(dep))
+        .collect()
+}
+
+pub fn is_unlocked(config: &Curriculum, progress: &Progress, id: &str) -> bool {
+    missing(config, progress, id).is_empty()
+}
+
+/// A requirement that names neither an exercise nor a category.
+pub fn dangling(config: &Curriculum) -> Vec<(String, String)> {
+    let known = |name: &str| {
+        config.exercises.iter().any(|e| e.id == name) || config.categories.contains_key(name)
+    };
+
+    let mut found = Vec::new();
+    for exercise in &config.exercises {
+        for requirement in &exercise.requires {
+            if !known(requirement) {
+                found.push((exercise.id.clone(), requirement.clone()));
+            }
+        }
+    }
+    for (name, category) in &config.categories {
+        for requirement in &category.requires {
+            if !known(requirement) {
+                found.push((name.clone(), requirement.clone()));
+            }
+        }
+    }
+    found
+}
+
+/// The first dependency cycle found, as the ids along it.
+pub fn find_cycle(config: &Curriculum) -> Option<Vec<String>> {
+    fn visit(
+        config: &Curriculum,
+        id: &str,
+        stack: &mut Vec<String>,
+        done: &mut HashSet<String>,
+    ) -> Option<Vec<String>> {
+        if let Some(start) = stack.iter().position(|s| s == id) {
+            let mut cycle = stack[start..].to_vec();
+            cycle.push(id.to_string());
+            return Some(cycle);
+        }
+        if done.contains(id) {
+            return None;
+        }
+
+        stack.push(id.to_string());
+        for dep in requirements(config, id) {
+            if let Some(cycle) = visit(config, &dep, stack, done) {
+                return Some(cycle);
+            }
+        }
+        stack.pop();
+        done.insert(id.to_string());
+        None
+    }
+
+    let mut done = HashSet::new();
+    config
+        .exercises
+        .iter()
+        .find_map(|e| visit(config, &e.id, &mut Vec::new(), &mut done))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(manifest: &str) -> Curriculum {
+        toml::from_str(manifest).unwrap()
+    }
+
+    const MANIFEST: &str = r#"
+        [[exercises]]
+        id = "a/one"
+        name = "One"
+        path = "exercises/a/one"
+        mode = "compile"
+        hint = ""
+
+        [[exercises]]
+        id = "a/two"
+        name = "Two"
+        path = "exercises/a/two"
+        mode = "compile"
+        hint = ""
+        requires = ["a/one"]
+
+        [[exercises]]
+        id = "b/three"
+        name = "Three"
+        path = "exercises/b/three"
+        mode = "test"
+        hint = ""
+
+        [categories]
+        a = ["a/one", "a/two"]
+        b = { exercises = ["b/three"], requires = ["a"] }
+    "#;
+
+    #[test]
+    fn expands_category_requirements() {
+        let config = config(MANIFEST);
+        assert_eq!(requirements(&config, "a/two"), vec!["a/one"]);
+        assert_eq!(requirements(&config, "b/three"), vec!["a/one", "a/two"]);
+
+        let mut progress = Progress::default();
+        progress.completed.insert("a/one".to_string());
+        assert!(is_unlocked(&config, &progress, "a/two"));
+        assert_eq!(missing(&config, &progress, "b/three"), vec!["a/two"]);
+    }
+
+    /// `MANIFEST` with `a/one` also requiring category `b`, which requires
+    /// `a` back, and an exercise that doesn't exist.
+    const BROKEN_MANIFEST: &str = r#"
+        [[exercises]]
+        id = "a/one"
+        name = "One"
+        path = "exercises/a/one"
+        mode = "compile"
+        hint = ""
+        requires = ["b", "c/missing"]
+
+        [[exercises]]
+        id = "a/two"
+        name = "Two"
+        path = "exercises/a/two"
+        mode = "compile"
+        hint = ""
+        requires = ["a/one"]
+
+        [[exercises]]
+        id = "b/three"
+        name = "Three"
+        path = "exercises/b/three"
+        mode = "test"
+        hint = ""
+
+        [categories]
+        a = ["a/one", "a/two"]
+        b = { exercises = ["b/three"], requires = ["a"] }
+    "#;
+
+    #[test]
+    fn detects_cycles_and_dangling_requirements() {
+        let config = config(BROKEN_MANIFEST);
+        assert!(find_cycle(&config).is_some());
+        assert_eq!(dangling(&config), vec![("a/one".to_string(), "c/missing".to_string())]);
+
+        let config = self::config(MANIFEST);
+        assert!(find_cycle(&config).is_none());
+        assert!(dangling(&config).is_empty());
+    }
+}
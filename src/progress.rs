@@ -0,0 +1,380 @@
+//! What the learner has done so far, kept per workspace in
+//! `.inklings/progress.json` so checkouts and course forks don't share it.
+//!
+//! The file carries a `version`; older layouts are migrated as they're read,
+//! and a newer one is refused rather than misread. Writes go through
+//! `Progress::update`, which holds a lock on `.inklings/progress.lock` across
+//! the read-modify-write so concurrent `watch` and `verify` runs don't lose
+//! each other's changes, and replaces the file atomically. A file that can't
+//! be read or parsed is an error, never silently treated as empty.
+//!
+//! Progress from before it was kept per workspace stays in
+//! `~/.inklings_progress.json` until `Progress::import_legacy` copies it in.
+//! That's a one-time step the learner asks for: it refuses once the
+//! workspace has progress of its own.
+
+use crate::verify::{Verification, VerifyOutcome};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::{HashMap, HashSet};
+use std::env;
+use std::error::Error;
+use std::fmt;
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+/// The layout progress is saved in. Bump it and add a step to `MIGRATIONS`
+/// whenever a field is renamed, removed or changes meaning.
+pub const VERSION: u64 = 2;
+
+/// `MIGRATIONS[n]` upgrades a version `n + 1` file to version `n + 2`.
+const MIGRATIONS: [fn(&mut Map<String, Value>); 1] = [
+    // 1 is the unversioned ~/.inklings_progress.json, from before basic1 and
+    // basic2 were renamed; fields it doesn't have yet deserialize to their
+    // defaults
+    |fields| {
+        rename_ids(fields, &[("02_basics/basic1", "02_basics/basics1"), ("02_basics/basic2", "02_basics/basics2")])
+    },
+];
+
+/// Renames exercise ids (`(old, new)` pairs) wherever progress mentions one.
+fn rename_ids(fields: &mut Map<String, Value>, renamed: &[(&str, &str)]) {
+    let rename = |id: &str| renamed.iter().find(|(old, _)| *old == id).map_or(id, |(_, new)| new).to_string();
+    if let Some(Value::Array(ids)) = fields.get_mut("completed") {
+        for id in ids {
+            if let Value::String(id) = id {
+                *id = rename(id);
+            }
+        }
+    }
+    for key in ["attempts", "hints_revealed", "test_scores", "verified_sources"] {
+        if let Some(Value::Object(by_id)) = fields.get_mut(key) {
+            *by_id = std::mem::take(by_id).into_iter().map(|(id, value)| (rename(&id), value)).collect();
+        }
+    }
+    if let Some(Value::String(id)) = fields.get_mut("last_worked_on") {
+        *id = rename(id);
+    }
+}
+
+/// Where every checkout kept its progress before it moved into the workspace.
+const LEGACY_FILE: &str = ".inklings_progress.json";
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct Progress {
+    pub completed: HashSet<String>,
+    #[serde(default)]
+    pub attempts: HashMap<String, u32>,
+    #[serde(default)]
+    pub last_worked_on: Option<String>,
+    #[serde(default)]
+    pub active_path: Option<String>,
+    /// Number of hint tiers revealed per exercise.
+    #[serde(default)]
+    pub hints_revealed: HashMap<String, usize>,
+    /// Latest unit test score per exercise, for partial credit.
+    #[serde(default)]
+    pub test_scores: HashMap<String, TestScore>,
+    /// Source hash of each exercise when it last passed verification.
+    #[serde(default)]
+    pub verified_sources: HashMap<String, String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TestScore {
+    pub passed: usize,
+    pub total: usize,
+}
+
+/// The progress file as written: `version` first, then the fields.
+#[derive(Serialize)]
+struct Stored<'a> {
+    version: u64,
+    #[serde(flatten)]
+    progress: &'a Progress,
+}
+
+/// Why progress couldn't be read or saved.
+#[derive(Debug)]
+pub enum ProgressError {
+    Io { path: PathBuf, source: io::Error },
+    /// The file isn't valid progress JSON.
+    Corrupt { path: PathBuf, message: String },
+    /// Written by a newer inklings with a layout this one doesn't know.
+    TooNew { path: PathBuf, version: u64 },
+    /// An import was asked for, but the workspace already has progress.
+    AlreadyStarted { path: PathBuf },
+}
+
+impl fmt::Display for ProgressError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProgressError::Io { path, source } => write!(f, "Couldn't access {}: {}", path.display(), source),
+            ProgressError::Corrupt { path, message } => write!(
+                f,
+                "{} is corrupt ({}). Fix it, or move it aside to start over.",
+                path.display(),
+                message
+            ),
+            ProgressError::TooNew { path, version } => write!(
+                f,
+                "{} was written by a newer inklings (version {}; this one reads up to {}).",
+                path.display(),
+                version,
+                VERSION
+            ),
+            ProgressError::AlreadyStarted { path } => write!(
+                f,
+                "{} already exists, so there's nothing to import into. Move it aside to import anyway.",
+                path.display()
+            ),
+        }
+    }
+}
+
+impl Error for ProgressError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ProgressError::Io { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+impl Progress {
+    /// `.inklings/progress.json` in the workspace, i.e. the directory inklings runs from.
+    pub fn file() -> PathBuf {
+        let file = Path::new(".inklings").join("progress.json");
+        env::current_dir().map_or(file.clone(), |cwd| cwd.join(file))
+    }
+
+    /// The shared file from before progress was kept per workspace.
+    pub fn legacy_file() -> Option<PathBuf> {
+        home::home_dir().map(|home| home.join(LEGACY_FILE))
+    }
+
+    /// Reads the workspace's progress; empty if there's none yet.
+    pub fn load() -> Result<Progress, ProgressError> {
+        Self::load_file(&Self::file())
+    }
+
+    /// Applies `change` to the stored progress and saves it, holding the
+    /// progress lock throughout.
+    pub fn update<T>(change: impl FnOnce(&mut Progress) -> T) -> Result<T, ProgressError> {
+        Self::update_file(&Self::file(), change)
+    }
+
+    /// Replaces the stored progress with an empty one, even if it was corrupt.
+    pub fn reset() -> Result<(), ProgressError> {
+        let path = Self::file();
+        let _lock = Self::lock(&path)?;
+        Progress::default().save(&path)
+    }
+
+    /// Copies the legacy file into a workspace that has no progress yet,
+    /// migrating it, and returns what was imported.
+    pub fn import_legacy() -> Result<Progress, ProgressError> {
+        let legacy = Self::legacy_file().unwrap_or_else(|| PathBuf::from(LEGACY_FILE));
+        Self::import_file(&Self::file(), &legacy)
+    }
+
+    fn import_file(path: &Path, legacy: &Path) -> Result<Progress, ProgressError> {
+        let _lock = Self::lock(path)?;
+        if path.exists() {
+            return Err(ProgressError::AlreadyStarted { path: path.to_path_buf() });
+        }
+        let content =
+            fs::read_to_string(legacy).map_err(|source| ProgressError::Io { path: legacy.to_path_buf(), source })?;
+        let progress = Self::parse(legacy, &content)?;
+        progress.save(path)?;
+        Ok(progress)
+    }
+
+    fn load_file(path: &Path) -> Result<Progress, ProgressError> {
+        match fs::read_to_string(path) {
+            Ok(content) => Self::parse(path, &content),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Progress::default()),
+            Err(source) => Err(ProgressError::Io { path: path.to_path_buf(), source }),
+        }
+    }
+
+    fn update_file<T>(path: &Path, change: impl FnOnce(&mut Progress) -> T) -> Result<T, ProgressError> {
+        let _lock = Self::lock(path)?;
+        let mut progress = Self::load_file(path)?;
+        let result = change(&mut progress);
+        progress.save(path)?;
+        Ok(result)
+    }
+
+    /// Takes the lock guarding `path`, creating its directory if need be.
+    /// Released when the returned file is dropped.
+    fn lock(path: &Path) -> Result<File, ProgressError> {
+        let io_error = |source| ProgressError::Io { path: path.to_path_buf(), source };
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(io_error)?;
+        }
+        let lock = File::create(path.with_extension("lock")).map_err(io_error)?;
+        lock.lock().map_err(io_error)?;
+        Ok(lock)
+    }
+
+    /// Parses a progress file of any known version, migrating it to the current layout.
+    fn parse(path: &Path, content: &str) -> Result<Progress, ProgressError> {
+        let corrupt = |message: String| ProgressError::Corrupt { path: path.to_path_buf(), message };
+        let mut value: Value = serde_json::from_str(content).map_err(|e| corrupt(e.to_string()))?;
+        let Some(fields) = value.as_object_mut() else {
+            return Err(corrupt("expected a JSON object".to_string()));
+        };
+
+        let version = match fields.remove("version") {
+            None => 1,
+            Some(version) => version.as_u64().filter(|v| *v >= 1).ok_or_else(|| corrupt(format!("bad version {}", version)))?,
+        };
+        if version > VERSION {
+            return Err(ProgressError::TooNew { path: path.to_path_buf(), version });
+        }
+        for migrate in &MIGRATIONS[version as usize - 1..] {
+            migrate(fields);
+        }
+        serde_json::from_value(value).map_err(|e| corrupt(e.to_string()))
+    }
+
+    /// Writes to a temporary file and renames it over `path`, so readers see
+    /// either the old progress or the new, never half of it.
+    fn save(&self, path: &Path) -> Result<(), ProgressError> {
+        let io_error = |source| ProgressError::Io { path: path.to_path_buf(), source };
+        let json = serde_json::to_string_pretty(&Stored { version: VERSION, progress: self })
+            .expect("progress always serializes");
+
+        let temp = path.with_extension("json.tmp");
+        let mut file = File::create(&temp).map_err(io_error)?;
+        file.write_all(json.as_bytes()).and_then(|_| file.sync_all()).map_err(io_error)?;
+        fs::rename(&temp, path).map_err(io_error)
+    }
+
+    /// Records a verification of `exercise_id`: an attempt if cargo ran, its
+    /// latest test score, and completion if it passed.
+    pub fn record(&mut self, exercise_id: &str, verification: &Verification) {
+        if let Some(score) = verification.score {
+            self.test_scores.insert(exercise_id.to_string(), score);
+        }
+        if verification.attempted {
+            *self.attempts.entry(exercise_id.to_string()).or_insert(0) += 1;
+            self.last_worked_on = Some(exercise_id.to_string());
+        }
+        if matches!(verification.outcome, VerifyOutcome::Passed) {
+            self.completed.insert(exercise_id.to_string());
+            if let Some(hash) = &verification.source_hash {
+                self.verified_sources.insert(exercise_id.to_string(), hash.clone());
+            }
+            self.last_worked_on = Some(exercise_id.to_string());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+
+    /// A fresh directory for one test's progress files.
+    fn scratch(name: &str) -> PathBuf {
+        let dir = env::temp_dir().join(format!("inklings-progress-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn migrates_unversioned_files() {
+        let legacy = r#"{
+            "completed": ["01_intro/intro1", "02_basics/basic1"],
+            "attempts": { "01_intro/intro1": 2, "02_basics/basic2": 3 },
+            "last_worked_on": "02_basics/basic2"
+        }"#;
+        let progress = Progress::parse(Path::new("p.json"), legacy).unwrap();
+        assert!(progress.completed.contains("01_intro/intro1"));
+        assert!(progress.completed.contains("02_basics/basics1"));
+        assert!(!progress.completed.contains("02_basics/basic1"));
+        assert_eq!(progress.attempts["01_intro/intro1"], 2);
+        assert_eq!(progress.attempts["02_basics/basics2"], 3);
+        assert_eq!(progress.last_worked_on.as_deref(), Some("02_basics/basics2"));
+        assert!(progress.test_scores.is_empty());
+
+        // Current files already use the new ids
+        let current = r#"{ "version": 2, "completed": ["02_basics/basic1"] }"#;
+        assert!(Progress::parse(Path::new("p.json"), current).unwrap().completed.contains("02_basics/basic1"));
+    }
+
+    #[test]
+    fn refuses_corrupt_and_newer_files() {
+        let path = Path::new("p.json");
+        assert!(matches!(Progress::parse(path, "{ \"completed\": ["), Err(ProgressError::Corrupt { .. })));
+        assert!(matches!(Progress::parse(path, "[]"), Err(ProgressError::Corrupt { .. })));
+        assert!(matches!(
+            Progress::parse(path, r#"{ "version": 0, "completed": [] }"#),
+            Err(ProgressError::Corrupt { .. })
+        ));
+        let newer = Progress::parse(path, r#"{ "version": 99, "completed": [] }"#);
+        assert!(matches!(newer, Err(ProgressError::TooNew { version: 99, .. })));
+    }
+
+    #[test]
+    fn updates_are_saved_with_their_version() {
+        let dir = scratch("save");
+        let path = dir.join(".inklings").join("progress.json");
+
+        Progress::update_file(&path, |p| p.active_path = Some("beginner".into())).unwrap();
+        let saved: Value = serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(saved["version"], VERSION);
+        let progress = Progress::load_file(&path).unwrap();
+        assert_eq!(progress.active_path.as_deref(), Some("beginner"));
+        assert!(!path.with_extension("json.tmp").exists());
+
+        fs::write(&path, "not json").unwrap();
+        assert!(Progress::load_file(&path).is_err());
+        assert!(Progress::update_file(&path, |_| ()).is_err());
+        assert_eq!(fs::read_to_string(&path).unwrap(), "not json");
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn legacy_progress_is_imported_once() {
+        let dir = scratch("import");
+        let path = dir.join(".inklings").join("progress.json");
+        let legacy = dir.join("legacy.json");
+
+        // Never read implicitly
+        fs::write(&legacy, r#"{ "completed": ["01_intro/intro1", "02_basics/basic1"] }"#).unwrap();
+        assert!(Progress::load_file(&path).unwrap().completed.is_empty());
+
+        let imported = Progress::import_file(&path, &legacy).unwrap();
+        assert!(imported.completed.contains("02_basics/basics1"));
+        let progress = Progress::load_file(&path).unwrap();
+        assert_eq!(progress.completed, imported.completed);
+
+        let again = Progress::import_file(&path, &legacy);
+        assert!(matches!(again, Err(ProgressError::AlreadyStarted { .. })));
+        let missing = Progress::import_file(&dir.join("other.json"), &dir.join("missing.json"));
+        assert!(matches!(missing, Err(ProgressError::Io { .. })));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn concurrent_updates_are_not_lost() {
+        let dir = scratch("lock");
+        let path = dir.join("progress.json");
+        thread::scope(|scope| {
+            for _ in 0..8 {
+                scope.spawn(|| {
+                    for _ in 0..5 {
+                        Progress::update_file(&path, |p| *p.attempts.entry("x".into()).or_insert(0) += 1).unwrap();
+                    }
+                });
+            }
+        });
+        assert_eq!(Progress::load_file(&path).unwrap().attempts["x"], 40);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,141 @@
+//! Turns what a learner types on the command line into an exercise from the
+//! manifest, with suggestions when it names none or several.
+
+use crate::{Curriculum, Exercise};
+use std::fmt;
+
+/// Why a command-line exercise argument didn't name exactly one exercise.
+#[derive(Debug, PartialEq, Eq)]
+pub enum ResolveError {
+    NotFound {
+        input: String,
+        suggestions: Vec<String>,
+    },
+    Ambiguous {
+        input: String,
+        candidates: Vec<String>,
+    },
+}
+
+impl fmt::Display for ResolveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ResolveError::NotFound { input, .. } => write!(f, "Exercise '{}' not found!", input),
+            ResolveError::Ambiguous { input, candidates } => write!(
+                f,
+                "'{}' matches several exercises: {}",
+                input,
+                candidates.join(", ")
+            ),
+        }
+    }
+}
+
+/// The last segment of an exercise id: `01_intro/intro1` -> `intro1`.
+pub fn short_name(id: &str) -> &str {
+    id.rsplit('/').next().unwrap_or(id)
+}
+
+/// Resolves a user-supplied exercise argument against the manifest.
+///
+/// Accepted forms, tried in order:
+/// - a full id (`01_intro/intro1`) or manifest path (`exercises/01_intro/intro1`)
+/// - a short name (`intro1`)
+/// - a 1-based position in the manifest (`3`)
+/// - a unique prefix of an id or short name (`storage2`, `03_st` is ambiguous)
+pub fn resolve<'a>(config: &'a Curriculum, input: &str) -> Result<&'a Exercise, ResolveError> {
+    let wanted = input.trim().trim_end_matches('/');
+    let exercises = &config.exercises;
+
+    if let Some(info) = exercises.iter().find(|e| e.id == wanted || e.path == wanted) {
+        return Ok(info);
+    }
+
+    let by_name: Vec<_> = exercises.iter().filter(|e| short_name(&e.id) == wanted).collect();
+    if let [info] = by_name.as_slice() {
+        return Ok(info);
+    }
+    if by_name.len() > 1 {
+        return Err(ambiguous(wanted, &by_name));
+    }
+
+    if let Ok(position) = wanted.parse::<usize>() {
+        return match position.checked_sub(1).and_then(|i| exercises.get(i)) {
+            Some(info) => Ok(info),
+            None => Err(ResolveError::NotFound {
+                input: wanted.to_string(),
+                suggestions: Vec::new(),
+            }),
+        };
+    }
+
+    let by_prefix: Vec<_> = exercises
+        .iter()
+        .filter(|e| e.id.starts_with(wanted) || short_name(&e.id).starts_with(wanted))
+        .collect();
+    match by_prefix.as_slice() {
+        [info] => Ok(info),
+        [] => Err(ResolveError::NotFound {
+            input: wanted.to_string(),
+            suggestions: suggestions(exercises, wanted),
+        }),
+        _ => Err(ambiguous(wanted, &by_prefix)),
+    }
+}
+
+fn ambiguous(input: &str, matches: &[&Exercise]) -> ResolveError {
+    ResolveError::Ambiguous {
+        input: input.to_string(),
+        candidates: matches.iter().map(|e| e.id.clone()).collect(),
+    }
+}
+
+/// Ids whose id or short name is within a small edit distance of `input`.
+fn suggestions(exercises: &[Exercise], input: &str) -> Vec<String> {
+    let mut scored: Vec<_> = exercises
+        .iter()
+        .map(|e| {
+            let distance = strsim::levenshtein(&e.id, input)
+                .min(strsim::levenshtein(short_name(&e.id), input));
+            (distance, e.id.clone())
+        })
+        .filter(|(distance, _)| *distance <= 3)
+        .collect();
+
+    scored.sort();
+    scored.into_iter().take(3).map(|(_, id)| id).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn id(input: &str) -> Result<String, ResolveError> {
+        let config = Curriculum::load().unwrap();
+        resolve(&config, input).map(|info| info.id.clone())
+    }
+
+    #[test]
+    fn resolves_every_form() {
+        assert_eq!(id("01_intro/intro1").unwrap(), "01_intro/intro1");
+        assert_eq!(id("exercises/01_intro/intro1/").unwrap(), "01_intro/intro1");
+        assert_eq!(id("intro1").unwrap(), "01_intro/intro1");
+        assert_eq!(id("3").unwrap(), "02_basics/basics1");
+        assert_eq!(id("03_storage/storage2").unwrap(), "03_storage/storage2");
+        assert_eq!(id("04_events/events2").unwrap(), "04_events/events2");
+        assert_eq!(id("02_basics/basics2").unwrap(), "02_basics/basics2");
+    }
+
+    #[test]
+    fn reports_ambiguity_and_typos() {
+        assert!(matches!(id("storage"), Err(ResolveError::Ambiguous { .. })));
+        assert!(matches!(id("0"), Err(ResolveError::NotFound { .. })));
+
+        match id("basic1") {
+            Err(ResolveError::NotFound { suggestions, .. }) => {
+                assert_eq!(suggestions.first().map(String::as_str), Some("02_basics/basics1"));
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+}
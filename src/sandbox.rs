@@ -0,0 +1,805 @@
+//! Runs a contract in-process on ink!'s off-chain engine.
+//!
+//! The exercise source is copied into a scratch crate under
+//! `inklings/run/` in the target directory, with a `#[ink::test]` driver injected into the
+//! contract module. The driver keeps one contract instance alive, reads
+//! requests (deploy, call, switch caller, advance blocks, ...) from stdin and
+//! answers with `@@inklings` lines describing what happened: return values,
+//! events and storage. No node is involved.
+//!
+//! Calls are checked and their arguments parsed using the interface read
+//! from the source (see `contract`), not from metadata. Events are decoded
+//! one field at a time from the types their structs declare, so they needn't
+//! derive `Debug`; if a field's type isn't `Decode` and `Debug` the whole
+//! event is printed as hex instead.
+
+use crate::backend::Backend;
+use crate::cargo::{CancelToken, RunError, Stream};
+use crate::contract::{self, Callable, ContractInfo, Field};
+use crate::diagnostics::{self, Diagnostic};
+use serde::Deserialize;
+use std::fmt;
+use std::fmt::Write as _;
+use std::fs;
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Child, ChildStdin, ChildStdout, Command, Stdio};
+
+/// The off-chain engine's default accounts, in the order it defines them.
+pub const DEV_ACCOUNTS: [&str; 6] = ["alice", "bob", "charlie", "django", "eve", "frank"];
+
+const MARKER: &str = "@@inklings ";
+
+/// Separates the fields of a request; arguments may contain spaces and commas.
+const SEPARATOR: char = '\u{1f}';
+
+/// A constructor or message invocation as typed by the learner, e.g. `transfer(bob, 10)`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Call {
+    pub name: String,
+    pub args: Vec<String>,
+}
+
+impl fmt::Display for Call {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}({})", self.name, self.args.join(", "))
+    }
+}
+
+impl Call {
+    pub fn parse(input: &str) -> Result<Call, String> {
+        let input = input.trim();
+        let (name, args) = match input.split_once('(') {
+            Some((name, rest)) => {
+                let Some(args) = rest.trim_end().strip_suffix(')') else {
+                    return Err(format!("'{}' is missing a closing parenthesis", input));
+                };
+                (name.trim(), contract::split_top_level(args))
+            }
+            None => (input, Vec::new()),
+        };
+
+        if name.is_empty() || !name.chars().all(|c| c.is_alphanumeric() || c == '_') {
+            return Err(format!("'{}' is not a constructor or message call", input));
+        }
+        Ok(Call { name: name.to_string(), args })
+    }
+}
+
+/// Something to do to the running contract.
+#[derive(Debug, Clone)]
+pub enum Request {
+    /// Instantiate the contract, replacing any previous instance.
+    Deploy { call: Call, value: u128 },
+    Call { call: Call, value: u128 },
+    /// Make the following calls from this dev account.
+    Caller(String),
+    AdvanceBlocks(u32),
+    SetTimestamp(u64),
+}
+
+/// What the driver observed while handling one request.
+#[derive(Debug, Default)]
+pub struct Response {
+    pub returned: Option<String>,
+    pub panic: Option<String>,
+    /// The request was rejected before reaching the contract, e.g. a malformed argument.
+    pub error: Option<String>,
+    pub events: Vec<String>,
+    /// Storage field values after the request, rendered with `Debug`.
+    pub fields: Vec<(String, String)>,
+    /// Block number and timestamp, reported after block changes.
+    pub block: Option<(u64, u64)>,
+}
+
+pub enum Failure {
+    /// The contract can't be driven, e.g. it has no storage struct yet.
+    Invalid(String),
+    /// The exercise, or the driver injected into it, doesn't compile.
+    Compile {
+        diagnostics: Vec<Diagnostic>,
+        /// The generated lib.rs the diagnostics point into.
+        source: String,
+        /// Lines past this one belong to the injected driver.
+        exercise_lines: usize,
+    },
+    /// cargo itself failed, or the driver couldn't be started.
+    Cargo(String),
+    /// The build was cancelled.
+    Cancelled,
+}
+
+/// The driver injected into the contract module. `__STORAGE__`, `__EVENTS__`,
+/// `__FIELDS__`, `__CONSTRUCTORS__` and `__MESSAGES__` are filled in per contract.
+const DRIVER: &str = r#"
+    #[cfg(test)]
+    #[allow(dead_code, unused_imports, unused_mut, unused_variables, clippy::all)]
+    mod __inklings_run {
+        // The contract usually defines its own `Result`, so std's is spelled out below
+        use super::*;
+        use std::io::BufRead;
+        use std::marker::PhantomData;
+        use std::panic::{catch_unwind, AssertUnwindSafe};
+
+        type Env = ink::env::DefaultEnvironment;
+
+        // Debug output where the type has it, a placeholder where it doesn't
+        struct Probe<'a, T>(&'a T);
+        trait ShowDebug { fn show(&self) -> String; }
+        impl<T: core::fmt::Debug> ShowDebug for Probe<'_, T> { fn show(&self) -> String { format!("{:?}", self.0) } }
+        trait ShowOpaque { fn show(&self) -> String; }
+        impl<T> ShowOpaque for &Probe<'_, T> { fn show(&self) -> String { String::from("<no Debug>") } }
+
+        // Event fields decoded one at a time where their types allow it, so an
+        // event needn't derive Debug; None leaves the raw bytes to show
+        struct FieldProbe<T>(PhantomData<T>);
+        trait DecodeField { fn show(&self, data: &mut &[u8]) -> Option<String>; }
+        impl<T: ink::scale::Decode + core::fmt::Debug> DecodeField for FieldProbe<T> {
+            fn show(&self, data: &mut &[u8]) -> Option<String> { T::decode(data).ok().map(|v| format!("{:?}", v)) }
+        }
+        trait OpaqueField { fn show(&self, data: &mut &[u8]) -> Option<String>; }
+        impl<T> OpaqueField for &FieldProbe<T> { fn show(&self, _: &mut &[u8]) -> Option<String> { None } }
+
+        // Arguments as the learner types them
+        trait FromArg: Sized { fn from_arg(raw: &str) -> core::result::Result<Self, String>; }
+        macro_rules! integers { ($($t:ty),*) => { $(
+            impl FromArg for $t {
+                fn from_arg(raw: &str) -> core::result::Result<Self, String> {
+                    raw.replace('_', "").parse().map_err(|_| format!("expected a {}, got '{}'", stringify!($t), raw))
+                }
+            }
+        )* } }
+        integers!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+        impl FromArg for bool {
+            fn from_arg(raw: &str) -> core::result::Result<Self, String> {
+                raw.parse().map_err(|_| format!("expected true or false, got '{}'", raw))
+            }
+        }
+        impl FromArg for String {
+            fn from_arg(raw: &str) -> core::result::Result<Self, String> {
+                Ok(raw.strip_prefix('"').and_then(|r| r.strip_suffix('"')).unwrap_or(raw).to_string())
+            }
+        }
+        impl FromArg for ink::primitives::H160 {
+            fn from_arg(raw: &str) -> core::result::Result<Self, String> {
+                if let Some((_, account)) = dev_accounts().into_iter().find(|(name, _)| name.eq_ignore_ascii_case(raw)) {
+                    return Ok(account);
+                }
+                let digits = raw
+                    .strip_prefix("0x")
+                    .filter(|h| h.len() == 40 && h.chars().all(|c| c.is_ascii_hexdigit()))
+                    .ok_or_else(|| format!("expected an account (alice, bob, ...) or a 0x-prefixed 20-byte address, got '{}'", raw))?;
+                let mut bytes = [0u8; 20];
+                for (i, byte) in bytes.iter_mut().enumerate() {
+                    *byte = u8::from_str_radix(&digits[2 * i..2 * i + 2], 16).unwrap();
+                }
+                Ok(Self::from(bytes))
+            }
+        }
+        impl FromArg for ink::primitives::U256 {
+            fn from_arg(raw: &str) -> core::result::Result<Self, String> { u128::from_arg(raw).map(Self::from) }
+        }
+        impl<T: FromArg> FromArg for Option<T> {
+            fn from_arg(raw: &str) -> core::result::Result<Self, String> {
+                match raw {
+                    "None" | "none" => Ok(None),
+                    _ => T::from_arg(raw.strip_prefix("Some(").and_then(|r| r.strip_suffix(')')).unwrap_or(raw)).map(Some),
+                }
+            }
+        }
+        impl<T: FromArg> FromArg for Vec<T> {
+            fn from_arg(raw: &str) -> core::result::Result<Self, String> {
+                let items = raw
+                    .strip_prefix('[')
+                    .and_then(|r| r.strip_suffix(']'))
+                    .ok_or_else(|| format!("expected a list like [a, b], got '{}'", raw))?;
+                items.split(',').map(str::trim).filter(|i| !i.is_empty()).map(T::from_arg).collect()
+            }
+        }
+
+        // Parameter types without FromArg still compile; they just can't be typed in
+        struct Parse<T>(PhantomData<T>);
+        trait ParseArg<T> { fn parse(&self, raw: &str) -> core::result::Result<T, String>; }
+        impl<T: FromArg> ParseArg<T> for Parse<T> { fn parse(&self, raw: &str) -> core::result::Result<T, String> { T::from_arg(raw.trim()) } }
+        trait CantParse<T> { fn parse(&self, raw: &str) -> core::result::Result<T, String>; }
+        impl<T> CantParse<T> for &Parse<T> {
+            fn parse(&self, _: &str) -> core::result::Result<T, String> {
+                Err(format!("{} values can't be typed in yet", core::any::type_name::<T>()))
+            }
+        }
+
+        fn hex(data: &[u8]) -> String {
+            let digits: String = data.iter().map(|b| format!("{:02x}", b)).collect();
+            format!("0x{}", digits)
+        }
+
+        fn dev_accounts() -> [(&'static str, ink::primitives::H160); 6] {
+            let a = ink::env::test::default_accounts();
+            [("alice", a.alice), ("bob", a.bob), ("charlie", a.charlie), ("django", a.django), ("eve", a.eve), ("frank", a.frank)]
+        }
+
+        fn describe(event: &ink::env::test::EmittedEvent) -> String {
+            let topic = event.topics.first().copied();
+__EVENTS__            format!("<unknown event> {}", hex(&event.data))
+        }
+
+        fn panic_message(payload: Box<dyn std::any::Any + Send>) -> String {
+            let message = payload
+                .downcast_ref::<&str>()
+                .map(|s| s.to_string())
+                .or_else(|| payload.downcast_ref::<String>().cloned())
+                .unwrap_or_else(|| String::from("<panic>"));
+            message.lines().collect::<Vec<_>>().join(" ")
+        }
+
+        fn report(contract: &__STORAGE__, events_seen: &mut usize) {
+            let events = ink::env::test::recorded_events();
+            for event in &events[*events_seen..] {
+                println!("@@inklings event {}", describe(event));
+            }
+            *events_seen = events.len();
+__FIELDS__        }
+
+        fn deploy(name: &str, args: &[&str]) -> core::result::Result<__STORAGE__, String> {
+            match name {
+__CONSTRUCTORS__                _ => Err(format!("unknown constructor '{}'", name)),
+            }
+        }
+
+        fn call(contract: &mut __STORAGE__, name: &str, args: &[&str]) -> core::result::Result<String, String> {
+            match name {
+__MESSAGES__                _ => Err(format!("unknown message '{}'", name)),
+            }
+        }
+
+        fn transfer(value: &str) -> core::result::Result<(), String> {
+            let value = u128::from_arg(value)?;
+            let callee = ink::env::test::callee();
+            let balance = ink::env::test::get_contract_balance::<Env>(callee).unwrap_or_default();
+            ink::env::test::set_contract_balance(callee, balance + ink::primitives::U256::from(value));
+            ink::env::test::set_value_transferred(value.into());
+            Ok(())
+        }
+
+        fn handle(command: &str, args: &[&str], contract: &mut Option<__STORAGE__>, events_seen: &mut usize) -> core::result::Result<(), String> {
+            let arg = |i: usize| args.get(i).copied().unwrap_or_default();
+            match command {
+                "deploy" => {
+                    transfer(arg(0))?;
+                    let deployed = catch_unwind(AssertUnwindSafe(|| deploy(arg(1), args.get(2..).unwrap_or_default())));
+                    ink::env::test::set_value_transferred(0.into());
+                    match deployed {
+                        Ok(deployed) => {
+                            let deployed = deployed?;
+                            report(&deployed, events_seen);
+                            *contract = Some(deployed);
+                        }
+                        Err(payload) => println!("@@inklings panic {}", panic_message(payload)),
+                    }
+                }
+                "call" => {
+                    let Some(instance) = contract.as_mut() else {
+                        return Err(String::from("deploy the contract first"));
+                    };
+                    transfer(arg(0))?;
+                    let returned = catch_unwind(AssertUnwindSafe(|| call(instance, arg(1), args.get(2..).unwrap_or_default())));
+                    ink::env::test::set_value_transferred(0.into());
+                    match returned {
+                        Ok(returned) => {
+                            println!("@@inklings return {}", returned?);
+                            report(instance, events_seen);
+                        }
+                        Err(payload) => println!("@@inklings panic {}", panic_message(payload)),
+                    }
+                }
+                "caller" => ink::env::test::set_caller(ink::primitives::H160::from_arg(arg(0))?),
+                "advance" => {
+                    for _ in 0..u32::from_arg(arg(0))? {
+                        ink::env::test::advance_block::<Env>();
+                    }
+                }
+                "timestamp" => ink::env::test::set_block_timestamp::<Env>(u64::from_arg(arg(0))?),
+                _ => return Err(format!("unknown request '{}'", command)),
+            }
+            if command == "advance" || command == "timestamp" {
+                println!("@@inklings block {} {}", ink::env::block_number::<Env>(), ink::env::block_timestamp::<Env>());
+            }
+            Ok(())
+        }
+
+        #[ink::test]
+        fn run() {
+            std::panic::set_hook(Box::new(|_| {}));
+            ink::env::test::set_caller(ink::env::test::default_accounts().alice);
+            let mut contract = None;
+            let mut events_seen = 0;
+
+            for line in std::io::stdin().lock().lines() {
+                let Ok(line) = line else { break };
+                let mut parts = line.split('\u{1f}');
+                let command = parts.next().unwrap_or_default();
+                let args: Vec<&str> = parts.collect();
+                if let Err(message) = handle(command, &args, &mut contract, &mut events_seen) {
+                    println!("@@inklings error {}", message);
+                }
+                println!("@@inklings done");
+            }
+        }
+    }
+"#;
+
+/// How a storage field's current value is shown, depending on its type.
+fn field_expr(field: &Field) -> String {
+    let access = format!("contract.{}", field.name);
+    if let Some(generics) = field.ty.strip_prefix("Mapping<") {
+        let key = contract::split_top_level(generics.trim_end_matches('>')).into_iter().next().unwrap_or_default();
+        return match key.as_str() {
+            // Mappings can't be iterated, but the dev accounts' entries are what the learner will look at
+            "H160" | "Address" => format!(
+                "{{ let entries: Vec<String> = dev_accounts().into_iter().filter_map(|(name, account)| \
+                 {access}.get(account).map(|v| format!(\"{{}}: {{}}\", name, (&Probe(&v)).show()))).collect(); \
+                 format!(\"{{{{{{}}}}}}\", entries.join(\", \")) }}"
+            ),
+            _ => "String::from(\"<mapping>\")".to_string(),
+        };
+    }
+    if field.ty.starts_with("Lazy<") {
+        return format!("{{ let v = {access}.get(); (&Probe(&v)).show() }}");
+    }
+    if field.ty.starts_with("StorageVec<") {
+        return format!(
+            "{{ let v: Vec<_> = (0..{access}.len()).filter_map(|i| {access}.get(i)).collect(); (&Probe(&v)).show() }}"
+        );
+    }
+    format!("(&Probe(&{access})).show()")
+}
+
+/// A match arm that parses `callable`'s arguments and invokes it through `target`.
+fn dispatch_arm(callable: &Callable, target: &str) -> String {
+    let mut arm = format!("                {:?} => {{\n", callable.name);
+    let _ = writeln!(
+        arm,
+        "                    if args.len() != {} {{ return Err(String::from({:?})); }}",
+        callable.params.len(),
+        format!("expected {} argument(s): {}", callable.params.len(), callable)
+    );
+    let mut names = Vec::new();
+    for (i, param) in callable.params.iter().enumerate() {
+        let _ = writeln!(
+            arm,
+            "                    let a{i} = (&Parse::<{}>(PhantomData)).parse(args[{i}]).map_err(|e| format!(\"{}: {{}}\", e))?;",
+            param.ty, param.name
+        );
+        names.push(format!("a{i}"));
+    }
+    let _ = writeln!(arm, "                    {}", target.replace("__ARGS__", &names.join(", ")));
+    arm.push_str("                }\n");
+    arm
+}
+
+/// Byte offset of the `}` closing the contract module. Braces in comments
+/// and literals don't count.
+fn module_end(source: &str, module: &str) -> Option<usize> {
+    let code = contract::code_only(source);
+    let start = code.find(&format!("mod {}", module))?;
+    let mut depth = 0;
+    for (i, byte) in code.bytes().enumerate().skip(start) {
+        match byte {
+            b'{' => depth += 1,
+            b'}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(i);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// The exercise source with the driver injected into its contract module.
+pub fn harness(info: &ContractInfo, source: &str) -> Result<String, String> {
+    if info.storage.is_empty() {
+        return Err("the contract has no #[ink(storage)] struct yet".to_string());
+    }
+    let Some(end) = module_end(source, &info.module) else {
+        return Err(format!("couldn't find the end of `mod {}`", info.module));
+    };
+
+    let mut events = String::new();
+    for event in &info.events {
+        let shown: Vec<_> = event.fields.iter().map(|f| format!("{}: {{}}", f.name)).collect();
+        let probes: String =
+            event.fields.iter().map(|f| format!(", (&FieldProbe::<{}>(PhantomData)).show(data)?", f.ty)).collect();
+        let _ = writeln!(
+            events,
+            "            if topic.is_some() && topic == <{name} as ink::env::Event>::SIGNATURE_TOPIC {{ \
+             let fields = (|| -> Option<String> {{ let data = &mut &event.data[..]; \
+             Some(format!(\"{{{{ {shown} }}}}\"{probes})) }})(); \
+             return format!(\"{name} {{}}\", fields.unwrap_or_else(|| hex(&event.data))); }}",
+            name = event.name,
+            shown = shown.join(", "),
+        );
+    }
+
+    let mut fields = String::new();
+    for field in &info.fields {
+        let _ = writeln!(fields, "            println!(\"{MARKER}field {} {{}}\", {});", field.name, field_expr(field));
+    }
+
+    let constructors: String = info
+        .constructors
+        .iter()
+        .map(|c| dispatch_arm(c, &format!("Ok({}::{}(__ARGS__))", info.storage, c.name)))
+        .collect();
+    let messages: String = info
+        .messages
+        .iter()
+        .map(|m| dispatch_arm(m, &format!("{{ let r = contract.{}(__ARGS__); Ok((&Probe(&r)).show()) }}", m.name)))
+        .collect();
+
+    let driver = DRIVER
+        .replace("__STORAGE__", &info.storage)
+        .replace("__EVENTS__", &events)
+        .replace("__FIELDS__", &fields)
+        .replace("__CONSTRUCTORS__", &constructors)
+        .replace("__MESSAGES__", &messages);
+
+    let mut generated = String::with_capacity(source.len() + driver.len());
+    generated.push_str(&source[..end]);
+    generated.push_str(&driver);
+    generated.push_str(&source[end..]);
+    Ok(generated)
+}
+
+/// The exercise's manifest, made standalone: `workspace = true` dependencies
+/// are resolved against the root manifest and dev-dependencies are dropped.
+fn standalone_manifest(exercise_path: &Path) -> Result<String, String> {
+    let read = |path: &Path| -> Result<toml::Table, String> {
+        let text = fs::read_to_string(path).map_err(|e| format!("couldn't read {}: {}", path.display(), e))?;
+        text.parse().map_err(|e| format!("couldn't parse {}: {}", path.display(), e))
+    };
+    let mut manifest = read(&exercise_path.join("Cargo.toml"))?;
+    let root = read(Path::new("Cargo.toml"))?;
+    let shared = root
+        .get("workspace")
+        .and_then(|w| w.get("dependencies"))
+        .and_then(|d| d.as_table())
+        .cloned()
+        .unwrap_or_default();
+
+    if let Some(dependencies) = manifest.get_mut("dependencies").and_then(|d| d.as_table_mut()) {
+        for (name, spec) in dependencies.iter_mut() {
+            let Some(table) = spec.as_table().filter(|t| t.get("workspace").and_then(|w| w.as_bool()) == Some(true))
+            else {
+                continue;
+            };
+            let mut resolved = match shared.get(name) {
+                Some(toml::Value::String(version)) => {
+                    toml::Table::from_iter([("version".to_string(), version.clone().into())])
+                }
+                Some(toml::Value::Table(t)) => t.clone(),
+                _ => return Err(format!("{} isn't in the root [workspace.dependencies]", name)),
+            };
+            for (key, value) in table.iter().filter(|(k, _)| *k != "workspace") {
+                match (key.as_str(), resolved.get_mut(key), value) {
+                    ("features", Some(toml::Value::Array(features)), toml::Value::Array(extra)) => {
+                        features.extend(extra.iter().cloned())
+                    }
+                    _ => {
+                        resolved.insert(key.clone(), value.clone());
+                    }
+                }
+            }
+            *spec = toml::Value::Table(resolved);
+        }
+    }
+
+    manifest.remove("dev-dependencies");
+    manifest.insert("lib".to_string(), toml::Value::Table(toml::Table::from_iter([("path".to_string(), "lib.rs".into())])));
+    manifest.insert("workspace".to_string(), toml::Value::Table(toml::Table::new()));
+    toml::to_string(&manifest).map_err(|e| e.to_string())
+}
+
+/// The parts of a `compiler-artifact` message needed to find the test binary.
+#[derive(Deserialize)]
+struct Artifact {
+    reason: String,
+    executable: Option<PathBuf>,
+}
+
+/// Builds the driver for the exercise at `exercise_path` in a scratch crate
+/// under `target_dir`, returning the test binary.
+fn build(
+    backend: &dyn Backend,
+    target_dir: &Path,
+    exercise_path: &Path,
+    info: &ContractInfo,
+    source: &str,
+    cancel: &CancelToken,
+    on_line: &mut dyn FnMut(Stream, &str),
+) -> Result<PathBuf, Failure> {
+    let generated = harness(info, source).map_err(Failure::Invalid)?;
+    let manifest = standalone_manifest(exercise_path).map_err(Failure::Cargo)?;
+
+    let dir = target_dir.join("inklings").join("run").join(&info.module);
+    let write = |name: &str, contents: &str| {
+        fs::write(dir.join(name), contents).map_err(|e| Failure::Cargo(format!("couldn't write {}: {}", name, e)))
+    };
+    fs::create_dir_all(&dir).map_err(|e| Failure::Cargo(format!("couldn't create {}: {}", dir.display(), e)))?;
+    write("Cargo.toml", &manifest)?;
+    write("lib.rs", &generated)?;
+    // Reuse the versions the rest of the workspace resolved to
+    if let Ok(lock) = fs::read_to_string("Cargo.lock") {
+        write("Cargo.lock", &lock)?;
+    }
+
+    let output = backend.build_driver(&dir, cancel, on_line).map_err(|e| match e {
+        RunError::Cancelled => Failure::Cancelled,
+        RunError::Io(e) => Failure::Cargo(format!("couldn't run cargo: {}", e)),
+    })?;
+    if output.success {
+        return output
+            .stdout
+            .lines()
+            .filter_map(|line| serde_json::from_str::<Artifact>(line).ok())
+            .filter(|a| a.reason == "compiler-artifact")
+            .find_map(|a| a.executable)
+            .ok_or_else(|| Failure::Cargo("cargo didn't report a test binary".to_string()));
+    }
+
+    let diagnostics = diagnostics::parse(&output.stdout, Path::new("lib.rs"));
+    if diagnostics.is_empty() {
+        return Err(Failure::Cargo(output.stderr));
+    }
+    Err(Failure::Compile {
+        diagnostics,
+        source: generated,
+        exercise_lines: source.lines().count(),
+    })
+}
+
+fn unknown(kind: &str, name: &str, known: &[Callable]) -> String {
+    let mut message = format!("the contract has no {} named '{}'", kind, name);
+    let close: Vec<_> = known
+        .iter()
+        .filter(|c| strsim::levenshtein(&c.name, name) <= 3)
+        .map(|c| c.name.as_str())
+        .collect();
+    if !close.is_empty() {
+        let _ = write!(message, " (did you mean {}?)", close.join(", "));
+    }
+    message
+}
+
+/// Catches mistakes the learner can fix before the request reaches the contract.
+/// Rejects a request the contract can't accept: an unknown constructor,
+/// message or account, the wrong number of arguments, or value sent to
+/// something that isn't payable.
+pub fn check(info: &ContractInfo, request: &Request, deployed: bool) -> Result<(), String> {
+    let (callable, call, value) = match request {
+        Request::Deploy { call, value } => match info.constructor(&call.name) {
+            Some(constructor) => (constructor, call, *value),
+            None if info.message(&call.name).is_some() => {
+                return Err(format!("'{}' is a message; deploy the contract with a constructor first", call.name));
+            }
+            None => return Err(unknown("constructor", &call.name, &info.constructors)),
+        },
+        Request::Call { call, value } => match info.message(&call.name) {
+            Some(_) if !deployed => return Err("deploy the contract with a constructor first".to_string()),
+            Some(message) => (message, call, *value),
+            None => return Err(unknown("message", &call.name, &info.messages)),
+        },
+        Request::Caller(account) if !DEV_ACCOUNTS.contains(&account.as_str()) => {
+            return Err(format!("unknown account '{}', pick one of {}", account, DEV_ACCOUNTS.join(", ")));
+        }
+        _ => return Ok(()),
+    };
+
+    if callable.params.len() != call.args.len() {
+        return Err(format!(
+            "{} takes {} argument(s), got {}: {}",
+            callable.name,
+            callable.params.len(),
+            call.args.len(),
+            callable
+        ));
+    }
+    if value > 0 && !callable.payable {
+        return Err(format!(
+            "{} isn't payable, so it would reject a transfer of {}. Mark it #[ink(message, payable)] to accept value.",
+            callable.name, value
+        ));
+    }
+    Ok(())
+}
+
+fn encode(request: &Request) -> String {
+    let mut fields = match request {
+        Request::Deploy { call, value } => vec!["deploy".to_string(), value.to_string(), call.name.clone()],
+        Request::Call { call, value } => vec!["call".to_string(), value.to_string(), call.name.clone()],
+        Request::Caller(account) => vec!["caller".to_string(), account.clone()],
+        Request::AdvanceBlocks(n) => vec!["advance".to_string(), n.to_string()],
+        Request::SetTimestamp(t) => vec!["timestamp".to_string(), t.to_string()],
+    };
+    if let Request::Deploy { call, .. } | Request::Call { call, .. } = request {
+        fields.extend(call.args.iter().cloned());
+    }
+    fields.join(&SEPARATOR.to_string())
+}
+
+/// Folds one `@@inklings` line into `response`. Returns false on `done`.
+fn apply(line: &str, response: &mut Response) -> bool {
+    // libtest prints `test ... ` without a newline before the test's own output
+    let Some((_, rest)) = line.split_once(MARKER) else {
+        return true;
+    };
+    let (kind, payload) = rest.split_once(' ').unwrap_or((rest, ""));
+    match kind {
+        "done" => return false,
+        "return" => response.returned = Some(payload.to_string()),
+        "panic" => response.panic = Some(payload.to_string()),
+        "error" => response.error = Some(payload.to_string()),
+        "event" => response.events.push(payload.to_string()),
+        "field" => {
+            let (name, value) = payload.split_once(' ').unwrap_or((payload, ""));
+            response.fields.push((name.to_string(), value.to_string()));
+        }
+        "block" => {
+            let mut numbers = payload.split(' ').filter_map(|n| n.parse().ok());
+            if let (Some(number), Some(timestamp)) = (numbers.next(), numbers.next()) {
+                response.block = Some((number, timestamp));
+            }
+        }
+        _ => {}
+    }
+    true
+}
+
+/// A running driver with one contract instance.
+pub struct Session {
+    info: ContractInfo,
+    child: Child,
+    stdin: ChildStdin,
+    stdout: BufReader<ChildStdout>,
+    deployed: bool,
+}
+
+impl Session {
+    /// Builds the exercise with the driver injected, in a scratch crate under
+    /// `target_dir`, and starts it. The build streams and cancels like a
+    /// verification stage.
+    pub fn start(
+        backend: &dyn Backend,
+        target_dir: &Path,
+        exercise_path: &Path,
+        info: &ContractInfo,
+        source: &str,
+        cancel: &CancelToken,
+        on_line: &mut dyn FnMut(Stream, &str),
+    ) -> Result<Session, Failure> {
+        let binary = build(backend, target_dir, exercise_path, info, source, cancel, on_line)?;
+        let mut child = Command::new(&binary)
+            .args(["__inklings_run", "--nocapture", "--test-threads=1"])
+            .env("RUST_BACKTRACE", "0")
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .map_err(|e| Failure::Cargo(format!("couldn't start {}: {}", binary.display(), e)))?;
+
+        let stdin = child.stdin.take().expect("stdin is piped");
+        let stdout = BufReader::new(child.stdout.take().expect("stdout is piped"));
+        Ok(Session { info: info.clone(), child, stdin, stdout, deployed: false })
+    }
+
+    /// Sends `request` and waits for the driver to finish handling it.
+    /// Mistakes in the request come back as `Response::error`; `Err` means the driver is gone.
+    pub fn send(&mut self, request: &Request) -> Result<Response, String> {
+        if let Err(message) = check(&self.info, request, self.deployed) {
+            return Ok(Response { error: Some(message), ..Response::default() });
+        }
+
+        writeln!(self.stdin, "{}", encode(request)).map_err(|e| format!("the contract process exited: {}", e))?;
+        let mut response = Response::default();
+        let mut line = String::new();
+        loop {
+            line.clear();
+            match self.stdout.read_line(&mut line) {
+                Ok(0) | Err(_) => return Err("the contract process exited unexpectedly".to_string()),
+                Ok(_) if !apply(line.trim_end(), &mut response) => break,
+                Ok(_) => {}
+            }
+        }
+
+        if matches!(request, Request::Deploy { .. }) && response.error.is_none() && response.panic.is_none() {
+            self.deployed = true;
+        }
+        Ok(response)
+    }
+}
+
+impl Drop for Session {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn events2() -> ContractInfo {
+        contract::parse(&fs::read_to_string("solutions/04_events/events2/lib.rs").unwrap()).unwrap()
+    }
+
+    #[test]
+    fn parses_calls() {
+        assert_eq!(Call::parse("flip").unwrap(), Call { name: "flip".into(), args: vec![] });
+        let call = Call::parse("transfer(bob, 1_000)").unwrap();
+        assert_eq!(call.args, ["bob", "1_000"]);
+        assert_eq!(call.to_string(), "transfer(bob, 1_000)");
+        assert!(Call::parse("transfer(bob").is_err());
+        assert!(Call::parse("a b").is_err());
+    }
+
+    #[test]
+    fn checks_requests_before_sending() {
+        let info = events2();
+        let call = |input: &str| Call::parse(input).unwrap();
+
+        assert!(check(&info, &Request::Deploy { call: call("new(10)"), value: 0 }, false).is_ok());
+        let early = check(&info, &Request::Call { call: call("total_supply"), value: 0 }, false);
+        assert!(early.unwrap_err().contains("deploy"));
+        let typo = check(&info, &Request::Call { call: call("transfr(bob, 1)"), value: 0 }, true);
+        assert!(typo.unwrap_err().contains("did you mean transfer"));
+        let arity = check(&info, &Request::Call { call: call("transfer(bob)"), value: 0 }, true);
+        assert!(arity.unwrap_err().contains("takes 2 argument(s)"));
+        let unpaid = check(&info, &Request::Call { call: call("mint(bob, 1)"), value: 5 }, true);
+        assert!(unpaid.unwrap_err().contains("isn't payable"));
+        assert!(check(&info, &Request::Caller("mallory".into()), true).is_err());
+    }
+
+    #[test]
+    fn injects_driver_into_contract_module() {
+        let source = fs::read_to_string("solutions/04_events/events2/lib.rs").unwrap();
+        let generated = harness(&events2(), &source).unwrap();
+
+        let driver = generated.find("mod __inklings_run").unwrap();
+        assert!(driver < generated.rfind('}').unwrap());
+        assert!(generated.contains("Ok(Events2::new(a0))"));
+        assert!(generated.contains("let r = contract.transfer(a0, a1);"));
+        assert!(generated.contains("(&Parse::<H160>(PhantomData)).parse(args[0])"));
+        assert!(generated.contains("<Transfer as ink::env::Event>::SIGNATURE_TOPIC"));
+        assert!(generated.contains("Some(format!(\"{{ from: {}, to: {}, value: {} }}\""));
+        assert!(generated.contains("(&FieldProbe::<Option<H160>>(PhantomData)).show(data)?"));
+        assert!(!generated.contains("__STORAGE__"));
+    }
+
+    #[test]
+    fn finds_module_end_past_braces_in_literals() {
+        let source = "#[ink::contract]\nmod m {\n    /* } */ const A: char = '}';\n    const B: &str = r#\"}\"#;\n}\nfn after() {}\n";
+        assert_eq!(module_end(source, "m"), source.find("}\nfn after"));
+    }
+
+    #[test]
+    fn encodes_and_reads_back_requests() {
+        let request = Request::Call { call: Call::parse("transfer(bob, 1_000)").unwrap(), value: 0 };
+        assert_eq!(encode(&request), "call\u{1f}0\u{1f}transfer\u{1f}bob\u{1f}1_000");
+
+        let mut response = Response::default();
+        for line in [
+            "test events2::__inklings_run::run ... @@inklings return Err(InsufficientBalance)",
+            "@@inklings event Transfer 0x00",
+            "@@inklings field total_supply 1000",
+        ] {
+            assert!(apply(line, &mut response));
+        }
+        assert!(!apply("@@inklings done", &mut response));
+        assert_eq!(response.returned.as_deref(), Some("Err(InsufficientBalance)"));
+        assert_eq!(response.events, ["Transfer 0x00"]);
+        assert_eq!(response.fields, [("total_supply".to_string(), "1000".to_string())]);
+    }
+}
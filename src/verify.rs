@@ -0,0 +1,360 @@
+//! Verifying an exercise: its prerequisites, whether it changed since it last
+//! passed, whether it's obviously unfinished, and then each stage of its mode
+//! in turn. The result is data for the caller to present and to record with
+//! `Progress::record`; nothing here prints.
+
+use crate::backend::{self, Backend, Output};
+use crate::cargo::{CancelToken, RunError, Stream};
+use crate::curriculum::{Curriculum, Exercise, Step};
+use crate::diagnostics::{self, Diagnostic};
+use crate::libtest::{self, Outcome, TestReport};
+use crate::precheck::{self, Pending};
+use crate::prereqs::{self, Policy};
+use crate::progress::{Progress, TestScore};
+use crate::{cache, hints};
+use std::io;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+/// Verifies exercises of one curriculum, with the backend its manifest selects
+/// unless another is given.
+///
+/// ```no_run
+/// use inklings::{CancelToken, Curriculum, Progress, Verifier, VerifyOutcome};
+///
+/// let curriculum = Curriculum::load().unwrap();
+/// let exercise = curriculum.find("basics1").unwrap();
+/// let progress = Progress::load().unwrap();
+/// let verification = Verifier::new(&curriculum)
+///     .verify(exercise, &progress, &CancelToken::default(), |_| {})
+///     .unwrap();
+/// if matches!(verification.outcome, VerifyOutcome::Passed) {
+///     println!("{} passed", exercise.id);
+/// }
+/// Progress::update(|progress| progress.record(&exercise.id, &verification)).unwrap();
+/// ```
+#[derive(Clone, Copy)]
+pub struct Verifier<'a> {
+    curriculum: &'a Curriculum,
+    /// None for the one the manifest selects.
+    backend: Option<&'a dyn Backend>,
+    force: bool,
+    fresh: bool,
+}
+
+/// Something that happened during a verification, as it happens.
+#[derive(Debug)]
+pub enum Event<'a> {
+    /// Exercises this one builds on aren't completed. If `enforced`, the
+    /// verification stops with `VerifyOutcome::Locked`.
+    Prerequisites { missing: &'a [String], enforced: bool },
+    /// The exercise is being verified rather than skipped as unchanged.
+    Started,
+    StageStarted(&'a Step),
+    /// A line of cargo's output from the running stage.
+    Output(Stream, &'a str),
+    StageFinished(&'a StageResult),
+}
+
+/// How one stage of the pipeline went.
+#[derive(Debug, Clone)]
+pub struct StageResult {
+    pub step: Step,
+    pub success: bool,
+    pub elapsed: Duration,
+    /// The unit test score, if the stage ran tests.
+    pub tests: Option<TestScore>,
+}
+
+/// How a verification ended.
+#[derive(Debug)]
+pub enum VerifyOutcome {
+    Passed,
+    /// Skipped: it passed before with the same source.
+    Unchanged,
+    /// Enforced prerequisites aren't completed.
+    Locked { missing: Vec<String> },
+    /// The source still has `todo!()` or the not-done marker, so cargo never ran.
+    Pending(Pending),
+    Failed(Failure),
+    Cancelled,
+}
+
+/// The stage that failed and why.
+#[derive(Debug)]
+pub struct Failure {
+    pub stage: StageResult,
+    /// Stages that didn't run because this one failed.
+    pub skipped: Vec<Step>,
+    pub details: FailureDetails,
+    /// Targeted hints from the manifest that match the failure.
+    pub hints: Vec<String>,
+}
+
+#[derive(Debug)]
+pub enum FailureDetails {
+    /// Compiler errors in the exercise's own lib.rs.
+    Diagnostics(Vec<Diagnostic>),
+    /// Tests ran and some of them failed.
+    Tests(TestReport),
+    /// cargo's output, when the failure happened somewhere else (a
+    /// dependency, the linker).
+    Output(String),
+}
+
+/// The result of `Verifier::verify`.
+#[derive(Debug)]
+pub struct Verification {
+    pub outcome: VerifyOutcome,
+    /// Whether cargo ran to completion at least once, which counts as an
+    /// attempt. A cancelled verification never does.
+    pub attempted: bool,
+    /// The latest unit test score, if any stage ran tests.
+    pub score: Option<TestScore>,
+    /// Hash of the sources that passed, so an unchanged exercise can be skipped next time.
+    pub source_hash: Option<String>,
+}
+
+impl<'a> Verifier<'a> {
+    pub fn new(curriculum: &'a Curriculum) -> Self {
+        Verifier { curriculum, backend: None, force: false, fresh: false }
+    }
+
+    pub fn backend(self, backend: &'a dyn Backend) -> Self {
+        Verifier { backend: Some(backend), ..self }
+    }
+
+    /// Verify even if enforced prerequisites are incomplete.
+    pub fn force(self, force: bool) -> Self {
+        Verifier { force, ..self }
+    }
+
+    /// Verify even if the source matches the last successful run.
+    pub fn fresh(self, fresh: bool) -> Self {
+        Verifier { fresh, ..self }
+    }
+
+    /// Verifies `exercise`, stopping at the first stage that fails. `progress`
+    /// is only read; pass the result to `Progress::record` to keep it. Fails
+    /// only if cargo couldn't be run at all.
+    pub fn verify(
+        &self,
+        exercise: &Exercise,
+        progress: &Progress,
+        cancel: &CancelToken,
+        mut on_event: impl FnMut(Event<'_>),
+    ) -> io::Result<Verification> {
+        let mut verification =
+            Verification { outcome: VerifyOutcome::Passed, attempted: false, score: None, source_hash: None };
+
+        let missing = prereqs::missing(self.curriculum, progress, &exercise.id);
+        if !missing.is_empty() {
+            let policy = self.curriculum.metadata.as_ref().map(|m| m.prerequisites).unwrap_or_default();
+            let enforced = policy == Policy::Enforce && !self.force;
+            on_event(Event::Prerequisites { missing: &missing, enforced });
+            if enforced {
+                verification.outcome = VerifyOutcome::Locked { missing };
+                return Ok(verification);
+            }
+        }
+
+        let source_hash = cache::source_hash(exercise).ok();
+        if !self.fresh
+            && let Some(hash) = &source_hash
+            && cache::is_unchanged(progress, &exercise.id, hash)
+        {
+            verification.outcome = VerifyOutcome::Unchanged;
+            return Ok(verification);
+        }
+        on_event(Event::Started);
+
+        // Skip cargo entirely when the exercise obviously hasn't been done yet
+        let exercise_path = Path::new(&exercise.path);
+        if let Ok(pending) = precheck::scan(exercise_path)
+            && pending.blocks_verification()
+        {
+            verification.outcome = VerifyOutcome::Pending(pending);
+            return Ok(verification);
+        }
+
+        let configured;
+        let backend = match self.backend {
+            Some(backend) => backend,
+            None => {
+                configured = backend::configured(self.curriculum);
+                &*configured
+            }
+        };
+        let steps = backend.steps(exercise.mode);
+        for (index, step) in steps.iter().enumerate() {
+            on_event(Event::StageStarted(step));
+            let started = Instant::now();
+            let on_line = &mut |stream, line: &str| on_event(Event::Output(stream, line));
+            let output = if step.runs_tests {
+                backend.test(exercise_path, step, cancel, on_line)
+            } else {
+                backend.compile(exercise_path, step, cancel, on_line)
+            };
+            let output = match output {
+                Ok(output) => output,
+                Err(RunError::Cancelled) => {
+                    // Stopped by a newer save or Ctrl+C, not a finished attempt
+                    verification.attempted = false;
+                    verification.outcome = VerifyOutcome::Cancelled;
+                    return Ok(verification);
+                }
+                Err(RunError::Io(e)) => return Err(e),
+            };
+            verification.attempted = true;
+
+            let report = if step.runs_tests {
+                libtest::parse(&output.stdout)
+            } else {
+                TestReport::default()
+            };
+            let tests = (!report.results.is_empty())
+                .then(|| TestScore { passed: report.count(Outcome::Passed), total: report.total() });
+            verification.score = tests.or(verification.score);
+
+            let stage = StageResult { step: *step, success: output.success, elapsed: started.elapsed(), tests };
+            on_event(Event::StageFinished(&stage));
+            if stage.success {
+                continue;
+            }
+
+            let (details, hints) = if tests.is_some() {
+                let hints = owned(hints::for_tests(exercise, &report));
+                (FailureDetails::Tests(report), hints)
+            } else {
+                compile_failure(exercise, &output)
+            };
+            let skipped = steps[index + 1..].to_vec();
+            verification.outcome = VerifyOutcome::Failed(Failure { stage, skipped, details, hints });
+            return Ok(verification);
+        }
+
+        verification.source_hash = source_hash;
+        Ok(verification)
+    }
+}
+
+/// The compiler errors in the exercise's own lib.rs, or cargo's output if the
+/// failure happened somewhere else, with the hints that match.
+fn compile_failure(exercise: &Exercise, output: &Output) -> (FailureDetails, Vec<String>) {
+    let diagnostics = diagnostics::parse(&output.stdout, &Path::new(&exercise.path).join("lib.rs"));
+    let hints = owned(hints::for_diagnostics(exercise, &diagnostics));
+
+    if diagnostics.is_empty() {
+        let mut text: String = output.stdout.lines().filter(|l| !l.starts_with('{')).map(|l| format!("{}\n", l)).collect();
+        text.push_str(&output.stderr);
+        (FailureDetails::Output(text), hints)
+    } else {
+        (FailureDetails::Diagnostics(diagnostics), hints)
+    }
+}
+
+fn owned(hints: Vec<&str>) -> Vec<String> {
+    hints.into_iter().map(|hint| hint.trim().to_string()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::FakeBackend;
+
+    const CURRICULUM: &str = r#"
+        [[exercises]]
+        id = "01_intro/intro1"
+        name = "Done"
+        path = "solutions/01_intro/intro1"
+        mode = "test"
+        hint = ""
+
+        [[exercises]]
+        id = "03_storage/storage2"
+        name = "Untouched"
+        path = "exercises/03_storage/storage2"
+        mode = "test"
+        hint = ""
+        "#;
+
+    const COMPILE_ERROR: &str = r#"{"reason":"compiler-message","message":{"message":"cannot find value `x` in this scope","level":"error","code":{"code":"E0425"},"spans":[{"file_name":"solutions/01_intro/intro1/lib.rs","line_start":3,"line_end":3,"column_start":5,"column_end":6,"is_primary":true,"label":null}]}}"#;
+
+    fn verify(backend: &FakeBackend, id: &str, progress: &Progress, cancel: &CancelToken) -> Verification {
+        let config: Curriculum = toml::from_str(CURRICULUM).unwrap();
+        let exercise = config.exercise(id).unwrap();
+        Verifier::new(&config).backend(backend).verify(exercise, progress, cancel, |_| {}).unwrap()
+    }
+
+    #[test]
+    fn passes_through_every_stage() {
+        let backend = FakeBackend::new().with("test", Output::passed("test tests::works ... ok\n"));
+        let verification = verify(&backend, "01_intro/intro1", &Progress::default(), &CancelToken::default());
+
+        assert!(matches!(verification.outcome, VerifyOutcome::Passed));
+        assert!(verification.attempted);
+        assert!(verification.source_hash.is_some());
+        assert_eq!(verification.score, Some(TestScore { passed: 1, total: 1 }));
+        assert_eq!(backend.calls(), ["check", "test"]);
+    }
+
+    #[test]
+    fn stops_at_the_first_failing_stage() {
+        let backend = FakeBackend::new().with("check", Output::failed(COMPILE_ERROR));
+        let verification = verify(&backend, "01_intro/intro1", &Progress::default(), &CancelToken::default());
+
+        let VerifyOutcome::Failed(failure) = verification.outcome else {
+            panic!("expected a failure, got {:?}", verification.outcome);
+        };
+        assert_eq!(failure.stage.step.subcommand, "check");
+        assert_eq!(failure.skipped.iter().map(|s| s.subcommand).collect::<Vec<_>>(), ["test"]);
+        assert!(matches!(&failure.details, FailureDetails::Diagnostics(d) if d.len() == 1));
+        assert!(verification.source_hash.is_none());
+        assert_eq!(backend.calls(), ["check"]);
+    }
+
+    #[test]
+    fn scores_failing_tests() {
+        let stdout = "test tests::a ... ok\ntest tests::b ... FAILED\n";
+        let backend = FakeBackend::new().with("test", Output::failed(stdout));
+        let verification = verify(&backend, "01_intro/intro1", &Progress::default(), &CancelToken::default());
+
+        assert_eq!(verification.score, Some(TestScore { passed: 1, total: 2 }));
+        let VerifyOutcome::Failed(failure) = verification.outcome else {
+            panic!("expected a failure, got {:?}", verification.outcome);
+        };
+        assert!(matches!(failure.details, FailureDetails::Tests(_)));
+    }
+
+    #[test]
+    fn skips_the_backend_when_nothing_would_pass() {
+        let backend = FakeBackend::new();
+        let pending = verify(&backend, "03_storage/storage2", &Progress::default(), &CancelToken::default());
+        assert!(matches!(pending.outcome, VerifyOutcome::Pending(_)));
+        assert!(!pending.attempted);
+
+        let config: Curriculum = toml::from_str(CURRICULUM).unwrap();
+        let mut progress = Progress::default();
+        let hash = cache::source_hash(config.exercise("01_intro/intro1").unwrap()).unwrap();
+        progress.completed.insert("01_intro/intro1".to_string());
+        progress.verified_sources.insert("01_intro/intro1".to_string(), hash);
+        let unchanged = verify(&backend, "01_intro/intro1", &progress, &CancelToken::default());
+        assert!(matches!(unchanged.outcome, VerifyOutcome::Unchanged));
+        assert!(backend.calls().is_empty());
+    }
+
+    #[test]
+    fn cancelling_ends_the_verification() {
+        let cancel = CancelToken::default();
+        cancel.cancel();
+        let verification = verify(&FakeBackend::new(), "01_intro/intro1", &Progress::default(), &cancel);
+        assert!(matches!(verification.outcome, VerifyOutcome::Cancelled));
+        assert!(!verification.attempted);
+
+        let mut progress = Progress::default();
+        progress.attempts.insert("01_intro/intro1".to_string(), 2);
+        progress.record("01_intro/intro1", &verification);
+        assert_eq!(progress.attempts["01_intro/intro1"], 2);
+        assert_eq!(progress.last_worked_on, None);
+    }
+}
@@ -0,0 +1,29 @@
+use std::process::{Command, Output};
+
+/// Runs the CLI from the repository root, where it finds the manifest.
+fn inklings(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_inklings-cli"))
+        .args(args)
+        .current_dir(env!("CARGO_MANIFEST_DIR"))
+        .output()
+        .expect("the CLI should start")
+}
+
+#[test]
+fn run_rejects_calls_the_contract_does_not_have() {
+    // `new` takes no arguments, and there is no `nosuch` message
+    for calls in [&["new(1000)"][..], &["new", "nosuch(1)"]] {
+        for format in ["text", "json"] {
+            let mut args = vec!["--format", format, "run", "02_basics/basics2"];
+            args.extend(calls);
+            let output = inklings(&args);
+            assert_eq!(output.status.code(), Some(2), "{:?}: {}", args, String::from_utf8_lossy(&output.stdout));
+        }
+    }
+
+    let output = inklings(&["--format", "json", "run", "02_basics/basics2", "new", "nosuch(1)"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let error: serde_json::Value = serde_json::from_str(stdout.lines().last().unwrap()).unwrap();
+    assert_eq!(error["kind"], "usage");
+    assert!(error["message"].as_str().unwrap().contains("no message named 'nosuch'"));
+}
@@ -1,11 +1,13 @@
 use anyhow::Result;
+use inklings::backend::{Backend, CargoBackend, Deployment};
+use inklings::cargo::CancelToken;
 use std::path::PathBuf;
 use std::process::Command;
 use subxt::{OnlineClient, PolkadotConfig};
-use subxt_signer::sr25519::dev;
 
 /// Helper struct for managing contract deployments
 struct ContractTest {
+    #[allow(dead_code)]
     client: OnlineClient<PolkadotConfig>,
     contract_path: PathBuf,
 }
@@ -29,7 +31,7 @@ impl ContractTest {
         println!("🔨 Building contract at {:?}", self.contract_path);
 
         let output = Command::new("cargo")
-            .args(&["contract", "build", "--release"])
+            .args(["contract", "build", "--release"])
             .current_dir(&self.contract_path)
             .output()?;
 
@@ -55,37 +57,19 @@ impl ContractTest {
 
         println!("📤 Deploying contract from {:?}", contract_file);
 
-        // Use cargo-contract to deploy
-        let mut cmd = Command::new("cargo");
-        cmd.args(&[
-            "contract",
-            "instantiate",
-            "--suri",
-            "//Alice",
-            "--constructor",
-            constructor,
-            "--skip-confirm",
-            "--execute",
-        ]);
+        // Deploy through the backend, into the target directory build_contract used
+        let backend = CargoBackend::new(self.contract_path.join("target"));
+        let deployment = Deployment::new(constructor).args(args);
+        let output = backend
+            .deploy(&self.contract_path, &deployment, &CancelToken::default(), &mut |_, _| {})
+            .map_err(|e| anyhow::anyhow!("Couldn't run cargo contract: {:?}", e))?;
 
-        for arg in args {
-            cmd.arg("--args").arg(arg);
-        }
-
-        let output = cmd
-            .current_dir(&self.contract_path)
-            .output()?;
-
-        if !output.status.success() {
-            anyhow::bail!(
-                "Contract deployment failed: {}",
-                String::from_utf8_lossy(&output.stderr)
-            );
+        if !output.success {
+            anyhow::bail!("Contract deployment failed: {}", output.stderr);
         }
 
         // Parse contract address from output
-        let output_str = String::from_utf8_lossy(&output.stdout);
-        let address = extract_contract_address(&output_str)
+        let address = extract_contract_address(&output.stdout)
             .ok_or_else(|| anyhow::anyhow!("Failed to extract contract address"))?;
 
         println!("✅ Contract deployed at: {}", address);
@@ -101,7 +85,7 @@ impl ContractTest {
         println!("📞 Calling contract method: {}", message);
 
         let mut cmd = Command::new("cargo");
-        cmd.args(&[
+        cmd.args([
             "contract",
             "call",
             "--suri",
@@ -141,7 +125,7 @@ impl ContractTest {
         println!("📖 Reading from contract: {}", message);
 
         let mut cmd = Command::new("cargo");
-        cmd.args(&[
+        cmd.args([
             "contract",
             "call",
             "--suri",